@@ -0,0 +1,42 @@
+// compares the old clone-everything table path with the borrowing one
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use process_monitor_and_manager::app::filter_sort_rows;
+use process_monitor_and_manager::models::{ProcRow, SettingsModel};
+
+// roughly what a busy desktop looks like
+fn sample_rows(n: usize) -> Vec<ProcRow> {
+    (0..n)
+        .map(|i| ProcRow {
+            pid: i as i32 + 1,
+            name: format!("process-{}", i * 7919 % n),
+            user: format!("user{}", i % 5),
+            priority: Some((i % 40) as i32 - 20),
+            cpu: (i * 37 % 100) as f32 / 3.0,
+            mem_bytes: (i as u64 * 104_729) % (1 << 30),
+            read_bps: Some((i as u64 * 31) % 4096),
+            write_bps: Some((i as u64 * 17) % 4096),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn bench_rows(c: &mut Criterion) {
+    let rows = sample_rows(600);
+    let settings = SettingsModel::default();
+
+    c.bench_function("clone rows then sort", |b| {
+        b.iter(|| {
+            let mut v: Vec<ProcRow> = rows.to_vec();
+            v.sort_by(|a, b| b.cpu.partial_cmp(&a.cpu).unwrap_or(std::cmp::Ordering::Equal));
+            black_box(v)
+        })
+    });
+
+    c.bench_function("borrow rows then sort", |b| {
+        b.iter(|| black_box(filter_sort_rows(&rows, &settings, None)))
+    });
+}
+
+criterion_group!(benches, bench_rows);
+criterion_main!(benches);
@@ -1,68 +1,178 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::time::{Duration, Instant};
-use iced::widget::{column, row, scrollable, Space};
-use iced::{executor, Application, Command, Element, Length, Subscription, Theme, Color};
-use sysinfo::{System, Networks};
+use iced::keyboard::{self, key};
+use iced::widget::{column, container, mouse_area, row, scrollable, text_input, Space};
+use iced::{event, executor, window, Application, Command, Element, Event, Length, Point, Subscription, Theme, Color};
+use regex::{Regex, RegexBuilder};
 
+use crate::alert_log;
+use crate::config;
+use crate::context_menu::context_area;
+use crate::notify;
+use crate::export::{self, JsonSnapshot};
 use crate::platform;
+#[cfg(feature = "metrics")]
+use crate::metrics::{self, MetricsSnapshot, SharedSnapshot};
+#[cfg(all(feature = "procnet", target_os = "linux"))]
+use crate::procnet;
+use crate::tray::{self, TrayCommand};
 use crate::models::*;
-use crate::suggestions::make_suggestions;
-use crate::system_monitor::{bytes_per_sec, total_disk_bytes, total_net_bytes};
-use crate::graphs::graph_card;
+use crate::suggestions::{make_suggestions, LEAK_SAMPLES};
+use crate::system_monitor::{ema, is_loopback, ExtraReads, process_details, prune_row_history, Monitor};
+use crate::graphs::{expanded_graph, graph_card, unavailable_card, GraphSpec};
+use crate::util::{find_ignore_case, fmt_bits_per_sec, fmt_bytes, fmt_celsius, fmt_load, fmt_percent, fmt_rate, fmt_utc};
 use crate::view::*;
 
 const TICK: Duration = Duration::from_millis(700);
+// refreshes this much slower than TICK mean the timer can't keep up
+const LAG_FACTOR: f32 = 1.5;
+// smoothing for the observed sample interval so one slow tick doesn't flash
+const SAMPLE_ALPHA: f32 = 0.3;
+// how long an error toast stays on screen
+const TOAST_TTL: Duration = Duration::from_secs(5);
+// oldest action records are dropped past this many
+const HISTORY_CAP: usize = 1000;
+
+// where an open row menu hangs from
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MenuAnchor {
+    Cursor(Point), // right click, relative to the row
+    Actions,       // the ⋯ button
+}
+
+// one of the platform functions that act on a pid
+type ProcAction = fn(i32) -> std::io::Result<()>;
+
+// what a critical-process confirmation goes on to do
+#[derive(Debug, Clone, Copy)]
+enum GuardedAction {
+    Run(ProcAction),
+    Signal(i32),
+    Restart, // runs off the UI thread, see ProcMonApp::restart
+}
 
 #[derive(Debug)]
 pub struct ProcMonApp {
-    sys: System,
-    networks: Networks,
-    last_io: HashMap<i32, IoSnapshot>,
-    last_net: (u64, u64),
-    last_disk: (u64, u64),
-    last_ts: Instant,
+    monitor: Monitor,
+    cpu_history: HashMap<i32, GraphSeries>, // short per-row history, see PROC_GRAPH_POINTS
+    mem_history: HashMap<i32, GraphSeries>, // bytes per row for the leak check, see LEAK_SAMPLES
+    breaches: HashMap<i32, Instant>, // watchdog: when each PID went over
 
     procs: Vec<ProcRow>,
     graphs: SystemGraphs,
+    net_rates: Vec<InterfaceRate>,
+    stats: SystemStats,
     settings: SettingsModel,
+    filter_re: Option<Regex>,
+    selected: Option<i32>,
+    details: Option<ProcDetails>,
+    env_filter: String,
+    env_revealed: bool, // show values of credential-looking variables
     suggestions: Vec<Suggestion>,
+    dismissed: HashSet<String>,
+    firing_alerts: HashSet<String>, // cpu/mem alert keys seen last tick
+    last_notified: HashMap<&'static str, Instant>, // per metric, for the cooldown
+    status_msg: Option<String>,
+    last_error: Option<(String, Instant)>,
     dot_phase: usize,
+    paused: bool,
+    graphs_paused: bool, // table stays live while the graphs hold still
+    expanded_graph: Option<GraphId>, // shown large over the table
+    sample_secs: Option<f32>, // observed time between refreshes, smoothed
+    disks: Vec<DiskUsage>, // capacity per mount, refreshed every few ticks
+    graph_clock: GraphClock, // graphs advance on it rather than every tick
+    column_menu_open: bool,
+    pending_kill: Option<i32>, // waiting for the user to confirm
+    pending_kill_all: Option<Vec<i32>>, // the filtered pids as of the click
+    // a kill aimed at a critical process, held for the stronger warning
+    pending_critical: Option<(i32, &'static str, GuardedAction)>,
+    checked: HashSet<i32>, // multi-select for the batch bar
+    seen_pids: Option<HashSet<i32>>, // previous refresh, None before the first
+    new_since: HashMap<i32, Instant>, // pids that just appeared, for the fade
+    launched: Vec<Launched>, // oldest first
+    history: VecDeque<ActionRecord>, // oldest first, capped at HISTORY_CAP
+    history_open: bool,
+    history_filter: String,
+    history_sort: (HistoryKey, SortDir),
+    #[cfg(target_os = "linux")]
+    affinity_edit: Option<(i32, Vec<bool>)>, // pid and one flag per core
+    #[cfg(target_family = "windows")]
+    pending_realtime: Option<i32>, // waiting for the realtime warning
+    expanded_groups: HashSet<String>, // names whose processes are listed
+    context_menu: Option<(i32, MenuAnchor)>,
+    tray_available: bool,
+    window_hidden: bool,
+    #[cfg(feature = "metrics")]
+    metrics: Option<SharedSnapshot>,
 }
 
 impl Application for ProcMonApp {
     type Executor = executor::Default;
     type Message = Message;
     type Theme = Theme;
-    type Flags = ();
-
-    fn new(_flags: Self::Flags) -> (Self, Command<Self::Message>) {
-        let mut sys = System::new_all();
-        sys.refresh_all();
+    type Flags = SettingsModel;
 
+    fn new(settings: Self::Flags) -> (Self, Command<Self::Message>) {
         let mut app = ProcMonApp {
-            sys,
-            networks: Networks::new_with_refreshed_list(),
-            last_io: HashMap::new(),
-            last_net: (0, 0),
-            last_disk: (0, 0),
-            last_ts: Instant::now(),
+            monitor: Monitor::new(),
+            cpu_history: HashMap::new(),
+            mem_history: HashMap::new(),
+            breaches: HashMap::new(),
             procs: Vec::new(),
             graphs: SystemGraphs::default(),
-            settings: SettingsModel {
-                thresholds: Thresholds {
-                    cpu_percent: 85,
-                    mem_percent: 90,
-                },
-                alerts_on_cpu: true,
-                alerts_on_mem: true,
-                sort_key: SortKey::Cpu,
-                sort_dir: SortDir::Desc,
-                ..Default::default()
-            },
+            net_rates: Vec::new(),
+            stats: SystemStats::default(),
+            settings,
+            filter_re: None,
+            selected: None,
+            details: None,
+            env_filter: String::new(),
+            env_revealed: false,
             suggestions: Vec::new(),
+            dismissed: HashSet::new(),
+            firing_alerts: HashSet::new(),
+            last_notified: HashMap::new(),
+            status_msg: None,
+            last_error: None,
             dot_phase: 0,
+            paused: false,
+            graphs_paused: false,
+            expanded_graph: None,
+            sample_secs: None,
+            disks: Vec::new(),
+            graph_clock: GraphClock::default(),
+            column_menu_open: false,
+            pending_kill: None,
+            pending_kill_all: None,
+            pending_critical: None,
+            checked: HashSet::new(),
+            seen_pids: None,
+            new_since: HashMap::new(),
+            launched: Vec::new(),
+            history: VecDeque::new(),
+            history_open: false,
+            history_filter: String::new(),
+            history_sort: (HistoryKey::Time, SortDir::Desc),
+            #[cfg(target_os = "linux")]
+            affinity_edit: None,
+            #[cfg(target_family = "windows")]
+            pending_realtime: None,
+            expanded_groups: HashSet::new(),
+            context_menu: None,
+            tray_available: tray::start(),
+            window_hidden: false,
+            #[cfg(feature = "metrics")]
+            metrics: None,
         };
 
+        #[cfg(feature = "metrics")]
+        match metrics::serve(&app.settings.metrics_addr) {
+            Ok(shared) => app.metrics = Some(shared),
+            Err(e) => eprintln!("metrics server on {} failed: {}", app.settings.metrics_addr, e),
+        }
+
+        app.compile_filter();
+        app.sync_smoothing();
         app.refresh_now();
         (app, Command::none())
     }
@@ -72,12 +182,57 @@ impl Application for ProcMonApp {
     }
 
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
+        // picking anything from the context menu closes it
+        if matches!(
+            message,
+            Message::Kill(_)
+                | Message::Terminate(_)
+                | Message::KillTree(_)
+                | Message::SendSignal(..)
+                | Message::Suspend(_)
+                | Message::Resume(_)
+                | Message::Boost(_)
+                | Message::Lower(_)
+                | Message::SetPriority(..)
+                | Message::CopyPid(_)
+                | Message::CopyName(_)
+                | Message::CopyRow(_)
+        ) {
+            self.context_menu = None;
+        }
+
         match message {
             Message::Tick => {
-                self.refresh_now();
-                self.dot_phase = (self.dot_phase + 1) % 4;
+                if matches!(&self.last_error, Some((_, at)) if at.elapsed() >= TOAST_TTL) {
+                    self.last_error = None;
+                }
+                if !self.paused {
+                    self.refresh_now();
+                    self.dot_phase = (self.dot_phase + 1) % 4;
+                }
+                self.poll_launched();
+                match tray::poll() {
+                    Some(TrayCommand::ToggleWindow) => {
+                        return self.set_window_hidden(!self.window_hidden)
+                    }
+                    Some(TrayCommand::Quit) => return self.quit(),
+                    None => {}
+                }
             },
-            Message::FilterChanged(s) => self.settings.filter = s,
+            Message::FilterChanged(s) => {
+                self.settings.filter = s;
+                self.compile_filter();
+            }
+            Message::FilterSubmitted => self.settings.remember_filter(),
+            Message::ApplyRecentFilter(filter) => {
+                self.settings.filter = filter;
+                self.settings.remember_filter();
+                self.compile_filter();
+            }
+            Message::RegexFilterChanged(v) => {
+                self.settings.filter_regex = v;
+                self.compile_filter();
+            }
             Message::SortBy(k) => {
                 if self.settings.sort_key == k {
                     self.settings.sort_dir = match self.settings.sort_dir {
@@ -89,58 +244,504 @@ impl Application for ProcMonApp {
                     self.settings.sort_dir = SortDir::Desc;
                 }
             }
-            Message::Kill(pid) => { let _ = platform::kill(pid); }
-            Message::Suspend(pid) => { let _ = platform::suspend(pid); }
-            Message::Resume(pid) => { let _ = platform::resume(pid); }
-            Message::Boost(pid) => { let _ = platform::priority_boost(pid); }
-            Message::Lower(pid) => { let _ = platform::priority_lower(pid); }
+            Message::Kill(pid) => return self.kill_checked("kill", pid, GuardedAction::Run(platform::kill)),
+            Message::Terminate(pid) => {
+                return self.kill_checked("terminate", pid, GuardedAction::Run(platform::terminate))
+            }
+            Message::KillTree(pid) => {
+                return self.kill_checked("kill tree of", pid, GuardedAction::Run(platform::kill_tree))
+            }
+            Message::ConfirmCritical => {
+                if let Some((pid, verb, action)) = self.pending_critical.take() {
+                    return self.run_guarded(verb, pid, action);
+                }
+            }
+            Message::CancelCritical => self.pending_critical = None,
+            Message::AllowCriticalChanged(v) => self.settings.allow_critical_kill = v,
+            Message::Suspend(pid) => self.report("suspend", pid, platform::suspend(pid)),
+            Message::Resume(pid) => self.report("resume", pid, platform::resume(pid)),
+            Message::Boost(pid) => self.report("boost", pid, platform::priority_boost(pid)),
+            Message::Lower(pid) => self.report("lower", pid, platform::priority_lower(pid)),
+            Message::SetPriority(pid, nice) => {
+                self.report("set priority of", pid, platform::set_priority(pid, nice))
+            }
+            Message::SendSignal(pid, signum) if platform::is_terminating_signal(signum) => {
+                return self.kill_checked("signal", pid, GuardedAction::Signal(signum))
+            }
+            Message::SendSignal(pid, signum) => {
+                self.report("signal", pid, platform::send_raw_signal(pid, signum))
+            }
+            Message::ReapZombies(pid) => self.report("signal", pid, platform::reap_children(pid)),
+            Message::Restart(pid) => {
+                self.context_menu = None;
+                return self.kill_checked("restart", pid, GuardedAction::Restart);
+            }
+            Message::RestartDone(pid, result) => {
+                self.record("restart", pid, result.clone());
+                match result {
+                    Ok(()) => self.status_msg = Some(format!("Restarted PID {}", pid)),
+                    Err(e) => {
+                        self.last_error = Some((format!("Failed to restart PID {}: {}", pid, e), Instant::now()))
+                    }
+                }
+            }
+            #[cfg(target_os = "linux")]
+            Message::EditAffinity(pid) => {
+                self.context_menu = None;
+                match platform::get_affinity(pid) {
+                    Ok(allowed) => {
+                        let cores = (0..self.monitor.system().cpus().len()).map(|c| allowed.contains(&c)).collect();
+                        self.affinity_edit = Some((pid, cores));
+                    }
+                    Err(e) => {
+                        self.last_error =
+                            Some((format!("Failed to read affinity of PID {}: {}", pid, e), Instant::now()))
+                    }
+                }
+            }
+            #[cfg(target_os = "linux")]
+            Message::AffinityCore(core, on) => {
+                if let Some(flag) = self.affinity_edit.as_mut().and_then(|(_, cores)| cores.get_mut(core)) {
+                    *flag = on;
+                }
+            }
+            #[cfg(target_os = "linux")]
+            Message::SetAffinity(pid, cpus) => {
+                self.affinity_edit = None;
+                self.report("set affinity of", pid, platform::set_affinity(pid, &cpus));
+            }
+            #[cfg(target_os = "linux")]
+            Message::CloseAffinity => self.affinity_edit = None,
+            #[cfg(target_family = "windows")]
+            Message::SetPriorityClass(pid, class) => {
+                self.context_menu = None;
+                if class == PriorityClass::Realtime {
+                    self.pending_realtime = Some(pid);
+                } else {
+                    self.report("set priority of", pid, platform::set_priority_class(pid, class));
+                }
+            }
+            #[cfg(target_family = "windows")]
+            Message::ConfirmRealtime => {
+                if let Some(pid) = self.pending_realtime.take() {
+                    self.report("set priority of", pid, platform::set_priority_class(pid, PriorityClass::Realtime));
+                }
+            }
+            #[cfg(target_family = "windows")]
+            Message::CancelRealtime => self.pending_realtime = None,
             Message::StartChanged(s) => self.settings.cmd_to_start = s,
-            Message::StartNow => { let _ = platform::start(&self.settings.cmd_to_start); }
+            Message::StartCwdChanged(s) => self.settings.start_cwd = s,
+            Message::StartEnvChanged(s) => self.settings.start_env = s,
+            Message::ToggleStartOptions => self.settings.start_options_open = !self.settings.start_options_open,
+            Message::StartDetachedChanged(v) => self.settings.start_detached = v,
+            Message::StartNow(req) => match start_command(&req) {
+                Ok(Some(child)) => {
+                    self.launched.push(Launched { cmd: req.cmd.trim().to_string(), child, exit: None });
+                }
+                Ok(None) => {}
+                Err(e) => self.last_error = Some((format!("Failed to start command: {}", e), Instant::now())),
+            },
+            Message::ClearLaunched => self.launched.retain(|l| l.exit.is_none()),
+            Message::ToggleHistory => self.history_open = !self.history_open,
+            Message::HistoryFilterChanged(s) => self.history_filter = s,
+            Message::EnvFilterChanged(s) => self.env_filter = s,
+            Message::ToggleEnvReveal => self.env_revealed = !self.env_revealed,
+            Message::SortHistory(key) => {
+                // same toggling as the process table headers
+                self.history_sort = match self.history_sort {
+                    (current, SortDir::Desc) if current == key => (key, SortDir::Asc),
+                    (current, SortDir::Asc) if current == key => (key, SortDir::Desc),
+                    _ => (key, SortDir::Desc),
+                };
+            }
             Message::CpuAlertChanged(v) => self.settings.alerts_on_cpu = v,
             Message::MemAlertChanged(v) => self.settings.alerts_on_mem = v,
+            Message::AlertLogChanged(v) => self.settings.alert_log = v,
+            Message::NotifyChanged(v) => self.settings.notify = v,
+            Message::NotifyCooldown(secs) => self.settings.notify_cooldown_secs = secs,
+            Message::DismissSuggestion(key) => {
+                self.suggestions.retain(|s| s.key != key);
+                self.dismissed.insert(key);
+            }
+            Message::SelectProcess(pid) => {
+                // clicking the selected row again closes the details pane
+                self.selected = if self.selected == Some(pid) { None } else { Some(pid) };
+                self.details = self.selected.and_then(|pid| process_details(self.monitor.system(), pid));
+                // secrets go back under wraps for every new selection
+                self.env_filter.clear();
+                self.env_revealed = false;
+            }
+            Message::TogglePause => {
+                self.paused = !self.paused;
+                if !self.paused {
+                    self.monitor.rebaseline();
+                }
+            }
+            Message::ToggleGraphsPause => self.graphs_paused = !self.graphs_paused,
+            Message::ExpandGraph(id) => self.expanded_graph = Some(id),
+            Message::CloseGraph => self.expanded_graph = None,
+            Message::WindowResized(width, height) => {
+                let geometry = self.window_geometry();
+                geometry.width = width as f32;
+                geometry.height = height as f32;
+            }
+            Message::WindowMoved(x, y) => {
+                let geometry = self.window_geometry();
+                geometry.x = Some(x);
+                geometry.y = Some(y);
+            }
+            Message::CloseRequested => {
+                if self.settings.minimize_to_tray && self.tray_available {
+                    return self.set_window_hidden(true);
+                }
+                return self.quit();
+            }
+            Message::MinimizeToTrayChanged(v) => self.settings.minimize_to_tray = v,
+            Message::FocusFilter => return text_input::focus(filter_input_id()),
+            Message::EscapePressed => {
+                // closes the innermost thing first: menu or graph, then confirmation
+                let closed = self.context_menu.take().is_some()
+                    || self.expanded_graph.take().is_some()
+                    || self.pending_critical.take().is_some()
+                    || self.pending_kill.take().is_some()
+                    || self.pending_kill_all.take().is_some();
+                #[cfg(target_family = "windows")]
+                let closed = closed || self.pending_realtime.take().is_some();
+                #[cfg(target_os = "linux")]
+                let closed = closed || self.affinity_edit.take().is_some();
+                if !closed {
+                    self.settings.filter.clear();
+                    self.compile_filter();
+                }
+            }
+            Message::KillSelected => self.pending_kill = self.selected,
+            Message::ConfirmKill => {
+                if let Some(pid) = self.pending_kill.take() {
+                    return self.kill_checked("kill", pid, GuardedAction::Run(platform::kill));
+                }
+            }
+            Message::CancelKill => self.pending_kill = None,
+            Message::KillAllMatching => {
+                let own_pid = sysinfo::get_current_pid().map(|p| p.as_u32() as i32).ok();
+                let pids: Vec<i32> = self
+                    .filtered_sorted_rows()
+                    .into_iter()
+                    .map(|p| p.pid)
+                    .filter(|&pid| Some(pid) != own_pid)
+                    .collect();
+                self.pending_kill_all = Some(pids).filter(|pids| !pids.is_empty());
+            }
+            Message::ConfirmKillAll => {
+                if let Some(pids) = self.pending_kill_all.take() {
+                    let total = pids.len();
+                    let killed = self.run_batch("kill", pids, platform::kill, true);
+                    self.status_msg = Some(format!("Killed {} of {} matching processes", killed, total));
+                }
+            }
+            Message::CancelKillAll => self.pending_kill_all = None,
+            Message::GroupByNameChanged(v) => self.settings.group_by_name = v,
+            Message::MineOnlyChanged(v) => self.settings.mine_only = v,
+            Message::CompactChanged(v) => self.settings.compact = v,
+            Message::RowLimitChanged(limit) => self.settings.row_limit = limit,
+            Message::SmoothCpuChanged(v) => {
+                self.settings.smooth_cpu = v;
+                self.sync_smoothing();
+            }
+            Message::IdleHogMemChanged(mib) => self.settings.thresholds.idle_mem_bytes = mib * 1024 * 1024,
+            Message::IdleHogCpuChanged(cpu) => self.settings.thresholds.idle_cpu_percent = cpu,
+            Message::CpuAlphaChanged(alpha) => {
+                self.settings.cpu_alpha = alpha;
+                self.sync_smoothing();
+            }
+            Message::CheckProcess(pid, v) => {
+                if v {
+                    self.checked.insert(pid);
+                } else {
+                    self.checked.remove(&pid);
+                }
+            }
+            Message::BatchKill => self.batch("kill", platform::kill, true),
+            Message::BatchSuspend => self.batch("suspend", platform::suspend, false),
+            Message::BatchResume => self.batch("resume", platform::resume, false),
+            Message::ClearChecked => self.checked.clear(),
+            // whole numbers only; clearing the box turns the filter off
+            Message::MinCpuChanged(s) => {
+                if let Ok(v) = parse_min(&s) {
+                    self.settings.min_cpu = v as f32;
+                }
+            }
+            Message::MinMemChanged(s) => {
+                if let Ok(v) = parse_min(&s) {
+                    self.settings.min_mem = v.saturating_mul(1024 * 1024);
+                }
+            }
+            Message::ToggleGroup(name) => {
+                if !self.expanded_groups.remove(&name) {
+                    self.expanded_groups.insert(name);
+                }
+            }
+            Message::ShowContextMenu(pid, at) => self.context_menu = Some((pid, MenuAnchor::Cursor(at))),
+            Message::ShowActionsMenu(pid) => self.context_menu = Some((pid, MenuAnchor::Actions)),
+            Message::CloseContextMenu => self.context_menu = None,
+            Message::CopyPid(pid) => return iced::clipboard::write(pid.to_string()),
+            Message::CopyName(name) => return iced::clipboard::write(name),
+            Message::CopyRow(pid) => {
+                if let Some(p) = self.procs.iter().find(|p| p.pid == pid) {
+                    return iced::clipboard::write(row_text(p, &self.settings.columns));
+                }
+            }
+            Message::ToggleColumnMenu => self.column_menu_open = !self.column_menu_open,
+            Message::ColumnVisible(id, visible) => {
+                let cols = &mut self.settings.columns;
+                if visible && !cols.contains(&id) {
+                    cols.push(id);
+                } else if !visible {
+                    cols.retain(|c| *c != id);
+                }
+            }
+            Message::MoveColumn(id, delta) => {
+                let cols = &mut self.settings.columns;
+                if let Some(i) = cols.iter().position(|c| *c == id) {
+                    let j = i as i32 + delta;
+                    if j >= 0 && (j as usize) < cols.len() {
+                        cols.swap(i, j as usize);
+                    }
+                }
+            }
+            Message::ToggleTheme => {
+                self.settings.theme = match self.settings.theme {
+                    ThemeChoice::Dark => ThemeChoice::Light,
+                    ThemeChoice::Light => ThemeChoice::Dark,
+                };
+            }
+            Message::NetInterfaceSelected(name) => {
+                self.settings.net_interface = (name != ALL_INTERFACES).then_some(name);
+                // old points came from a different interface set
+                self.graphs.net_rx = GraphSeries::default();
+                self.graphs.net_tx = GraphSeries::default();
+            }
+            Message::ExcludeLoopbackChanged(v) => {
+                self.settings.exclude_loopback = v;
+                self.graphs.net_rx = GraphSeries::default();
+                self.graphs.net_tx = GraphSeries::default();
+            }
+            Message::NetBitsChanged(v) => self.settings.net_bits = v,
+            Message::WatchdogEnabled(v) => {
+                self.settings.watchdog.enabled = v;
+                self.breaches.clear();
+            }
+            Message::WatchdogMetric(m) => {
+                self.settings.watchdog.metric = m;
+                self.breaches.clear();
+            }
+            Message::WatchdogThreshold(t) => self.settings.watchdog.threshold = t,
+            Message::WatchdogDuration(d) => self.settings.watchdog.duration_secs = d,
+            Message::WatchdogAction(a) => self.settings.watchdog.action = a,
+            Message::ExportCsv => {
+                let path = export::default_export_path("csv");
+                let result = export::export_csv(&self.filtered_sorted_rows(), &path);
+                self.status_msg = Some(match result {
+                    Ok(()) => format!("Exported to {}", path.display()),
+                    Err(e) => format!("Export failed: {}", e),
+                });
+            }
+            Message::ExportJson => {
+                let path = export::default_export_path("json");
+                let snapshot = JsonSnapshot::new(
+                    &self.stats,
+                    Some(&self.graphs),
+                    self.procs.iter().collect(),
+                    &self.suggestions,
+                );
+                self.status_msg = Some(match export::export_json(&snapshot, &path) {
+                    Ok(()) => format!("Exported to {}", path.display()),
+                    Err(e) => format!("Export failed: {}", e),
+                });
+            }
         }
         Command::none()
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
-        iced::time::every(TICK).map(|_| Message::Tick)
+        Subscription::batch([
+            iced::time::every(TICK).map(|_| Message::Tick),
+            event::listen_with(|event, status| match event {
+                // the filter box swallows Esc to unfocus, but it should still clear
+                Event::Keyboard(keyboard::Event::KeyPressed { key: key::Key::Named(key::Named::Escape), .. }) => {
+                    Some(Message::EscapePressed)
+                }
+                // other shortcuts stay out of the way while a text input has focus
+                Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. })
+                    if status == event::Status::Ignored && !modifiers.command() =>
+                {
+                    shortcut(key)
+                }
+                Event::Window(_, window::Event::Resized { width, height }) => {
+                    Some(Message::WindowResized(width, height))
+                }
+                Event::Window(_, window::Event::Moved { x, y }) => Some(Message::WindowMoved(x, y)),
+                Event::Window(_, window::Event::CloseRequested) => Some(Message::CloseRequested),
+                _ => None,
+            }),
+        ])
     }
 
     fn theme(&self) -> Self::Theme {
-        Theme::Dark
+        self.settings.theme.theme()
     }
 
     fn view(&self) -> Element<'_, Self::Message> {
-        let controls = controls_row(&self.settings);
+        // only offered while a filter narrows the table
+        let matching = (!self.settings.filter.trim().is_empty()).then(|| self.filtered_sorted_rows().len());
+        let controls = controls_row(&self.settings, self.filter_invalid(), self.paused, self.graphs_paused, matching);
         let header = table_header(&self.settings);
-        let top = top_bar(self.procs.len(), self.dot_phase);
+        let confirm = match self.pending_kill {
+            Some(pid) => {
+                let name = self.procs.iter().find(|p| p.pid == pid).map_or("", |p| p.name.as_str());
+                confirm_kill_bar(pid, name)
+            }
+            None => Space::with_height(0).into(),
+        };
+        let confirm_all = match &self.pending_kill_all {
+            Some(pids) => confirm_kill_all_bar(pids.len()),
+            None => Space::with_height(0).into(),
+        };
+        #[cfg(target_os = "linux")]
+        let affinity = match &self.affinity_edit {
+            Some((pid, cores)) => {
+                let name = self.procs.iter().find(|p| p.pid == *pid).map_or("", |p| p.name.as_str());
+                affinity_bar(*pid, name, cores)
+            }
+            None => Space::with_height(0).into(),
+        };
+        #[cfg(not(target_os = "linux"))]
+        let affinity: Element<'_, Message> = Space::with_height(0).into();
+        #[cfg(target_family = "windows")]
+        let realtime = match self.pending_realtime {
+            Some(pid) => {
+                let name = self.procs.iter().find(|p| p.pid == pid).map_or("", |p| p.name.as_str());
+                realtime_bar(pid, name)
+            }
+            None => Space::with_height(0).into(),
+        };
+        #[cfg(not(target_family = "windows"))]
+        let realtime: Element<'_, Message> = Space::with_height(0).into();
+        let critical = match self.pending_critical {
+            Some((pid, verb, _)) => {
+                let name = self.procs.iter().find(|p| p.pid == pid).map_or("", |p| p.name.as_str());
+                critical_kill_bar(pid, name, verb)
+            }
+            None => Space::with_height(0).into(),
+        };
+        let batch = if self.checked.is_empty() {
+            Space::with_height(0).into()
+        } else {
+            batch_bar(self.checked.len())
+        };
+        let column_menu = if self.column_menu_open {
+            column_menu(&self.settings.columns)
+        } else {
+            Space::with_height(0).into()
+        };
+        let sampling = self.sample_secs.map(|secs| (secs, sampling_lags(secs)));
+        let top = top_bar(self.dot_phase, &self.stats, self.paused, sampling);
+        let summary = summary_strip(&self.graphs, if self.settings.net_bits { fmt_bits_per_sec } else { fmt_rate });
+        let status = status_line(self.status_msg.as_deref());
+        let toast = error_toast(
+            self.last_error
+                .as_ref()
+                .filter(|(_, at)| at.elapsed() < TOAST_TTL)
+                .map(|(msg, _)| msg.as_str()),
+        );
 
-        let rows = self.filtered_sorted_rows()
-            .into_iter()
-            .map(|p| process_row(&p));
+        let rows = self.table_rows();
 
         let table = scrollable(column(rows).spacing(2)).height(Length::FillPortion(3));
 
-        let graphs = row![
-            graph_card("CPU", &self.graphs.cpu, Color::from_rgb(1.0, 0.3, 0.3)),
-            graph_card("Mem", &self.graphs.mem, Color::from_rgb(0.3, 1.0, 0.3)),
-            graph_card("Disk R", &self.graphs.disk_read, Color::from_rgb(0.3, 0.8, 1.0)),
-            graph_card("Disk W", &self.graphs.disk_write, Color::from_rgb(1.0, 0.8, 0.3)),
-            graph_card("Net RX", &self.graphs.net_rx, Color::from_rgb(1.0, 0.5, 1.0)),
-            graph_card("Net TX", &self.graphs.net_tx, Color::from_rgb(0.8, 0.3, 1.0)),
-        ]
-        .spacing(12)
-        .height(Length::FillPortion(1));
+        let frozen = self.graphs_paused;
+        let mut graphs = row![];
+        for id in [GraphId::Cpu, GraphId::Mem, GraphId::Swap, GraphId::DiskRead, GraphId::DiskWrite, GraphId::NetRx, GraphId::NetTx] {
+            graphs = graphs.push(graph_card(self.graph_spec(id), frozen));
+        }
+        graphs = graphs.push(if self.graphs.temp.points.is_empty() {
+            unavailable_card("Temp °C")
+        } else {
+            graph_card(self.graph_spec(GraphId::Temp), frozen)
+        });
+        if self.stats.load_avg.is_some() {
+            graphs = graphs.push(graph_card(self.graph_spec(GraphId::Load), frozen));
+        }
+        if self.monitor.has_gpu() {
+            graphs = graphs
+                .push(graph_card(self.graph_spec(GraphId::Gpu), frozen))
+                .push(graph_card(self.graph_spec(GraphId::Vram), frozen));
+        }
+        // the expanded view floats above the cards, a click elsewhere closes it
+        let expanded = self
+            .expanded_graph
+            .map(|id| (Point::ORIGIN, expanded_graph(self.graph_spec(id), GRAPH_INTERVAL.as_secs_f32())));
+        let graphs = context_area(
+            graphs.spacing(12),
+            expanded,
+            |_| Message::CloseGraph,
+            Message::CloseGraph,
+        );
+        let graphs = container(graphs).height(Length::FillPortion(1));
 
-        let alerts = alert_controls(&self.settings);
+        let details = match &self.details {
+            Some(d) => details_panel(d, &self.env_filter, self.env_revealed),
+            None => Space::with_height(0).into(),
+        };
+
+        let net_controls = network_controls(&self.net_rates, &self.settings);
+        let disks = disk_panel(&self.disks);
+        let launched = if self.launched.is_empty() {
+            Space::with_height(0).into()
+        } else {
+            launched_panel(&self.launched)
+        };
+
+        let history = if self.history_open {
+            let (key, dir) = self.history_sort;
+            history_panel(history_rows(&self.history, &self.history_filter, key, dir), &self.history_filter, key, dir)
+        } else {
+            Space::with_height(0).into()
+        };
+
+        let alerts = row![
+            alert_controls(&self.settings),
+            Space::with_width(40.0),
+            watchdog_controls(&self.settings.watchdog),
+            Space::with_width(40.0),
+            idle_hog_controls(&self.settings.thresholds),
+            Space::with_width(40.0),
+            tray_controls(&self.settings, self.tray_available),
+            Space::with_width(40.0),
+            critical_controls(&self.settings),
+        ];
         let sugg = suggestions_view(&self.suggestions);
 
         column![
             top,
+            summary,
+            toast,
+            confirm,
+            confirm_all,
+            critical,
+            realtime,
+            affinity,
+            batch,
             Space::with_height(4),
             controls,
+            status,
+            column_menu,
             header,
             table,
+            details,
+            launched,
+            history,
+            net_controls,
+            disks,
             graphs,
             Space::with_height(4),
             alerts,
@@ -152,103 +753,740 @@ impl Application for ProcMonApp {
     }
 }
 
+// whether refreshes arrive so late that per-second rates cover a longer
+// interval than the user expects
+pub fn sampling_lags(observed_secs: f32) -> bool {
+    observed_secs > TICK.as_secs_f32() * LAG_FACTOR
+}
+
+// marks pids missing from the previous refresh as new as of now, forgets
+// marks that have faded or whose process is gone, and returns this
+// refresh's pids. Nothing is new on the first refresh, everything would be
+pub fn track_new_pids(
+    prev: Option<&HashSet<i32>>,
+    rows: &[ProcRow],
+    new_since: &mut HashMap<i32, Instant>,
+    now: Instant,
+) -> HashSet<i32> {
+    let live: HashSet<i32> = rows.iter().map(|p| p.pid).collect();
+    if let Some(prev) = prev {
+        for &pid in live.difference(prev) {
+            new_since.insert(pid, now);
+        }
+    }
+    new_since.retain(|pid, at| live.contains(pid) && now.duration_since(*at) < NEW_PROCESS_HIGHLIGHT);
+    live
+}
+
+// 1 for a process that just appeared fading to 0 over NEW_PROCESS_HIGHLIGHT
+pub fn new_fade(since: Option<Instant>, now: Instant) -> f32 {
+    since.map_or(0.0, |at| {
+        1.0 - (now.duration_since(at).as_secs_f32() / NEW_PROCESS_HIGHLIGHT.as_secs_f32()).min(1.0)
+    })
+}
+
+// history records matching the search box, sorted by the chosen column.
+// Records are stored oldest first, so ties keep the order they happened in
+pub fn history_rows<'a>(
+    records: impl IntoIterator<Item = &'a ActionRecord>,
+    filter: &str,
+    key: HistoryKey,
+    dir: SortDir,
+) -> Vec<&'a ActionRecord> {
+    let needle = filter.trim();
+    let mut rows: Vec<&ActionRecord> = records
+        .into_iter()
+        .filter(|r| {
+            needle.is_empty()
+                || r.pid.to_string().contains(needle)
+                || [r.name.as_str(), r.action.as_str(), r.result.as_ref().err().map_or("", |e| e.as_str())]
+                    .iter()
+                    .any(|field| find_ignore_case(field, needle).is_some())
+        })
+        .collect();
+    match key {
+        HistoryKey::Time => {}
+        HistoryKey::Pid => rows.sort_by_key(|r| r.pid),
+        HistoryKey::Name => rows.sort_by_key(|r| r.name.to_lowercase()),
+        HistoryKey::Action => rows.sort_by(|a, b| a.action.cmp(&b.action)),
+        // failures first when descending
+        HistoryKey::Result => rows.sort_by_key(|r| r.result.is_err()),
+    }
+    if dir == SortDir::Desc {
+        rows.reverse();
+    }
+    rows
+}
+
+// global keys: `/` filter, Delete kill, Space pause, Enter confirm
+fn shortcut(key: key::Key) -> Option<Message> {
+    match key.as_ref() {
+        key::Key::Character("/") => Some(Message::FocusFilter),
+        key::Key::Named(key::Named::Delete) => Some(Message::KillSelected),
+        key::Key::Named(key::Named::Space) => Some(Message::TogglePause),
+        key::Key::Named(key::Named::Enter) => Some(Message::ConfirmKill),
+        _ => None,
+    }
+}
+
 impl ProcMonApp {
+    // saves settings, then closes the window which ends the app
+    fn quit(&mut self) -> Command<Message> {
+        if let Err(e) = config::save_settings(&self.settings) {
+            eprintln!("failed to save settings: {}", e);
+        }
+        window::close(window::Id::MAIN)
+    }
+
+    fn set_window_hidden(&mut self, hidden: bool) -> Command<Message> {
+        self.window_hidden = hidden;
+        let mode = if hidden { window::Mode::Hidden } else { window::Mode::Windowed };
+        window::change_mode(window::Id::MAIN, mode)
+    }
+
+    // geometry to update from window events, starting from iced's default size
+    fn window_geometry(&mut self) -> &mut WindowGeometry {
+        let default_size = window::Settings::default().size;
+        self.settings.window.get_or_insert(WindowGeometry {
+            width: default_size.width,
+            height: default_size.height,
+            x: None,
+            y: None,
+        })
+    }
+
+    // remember a failed action so view can show it as a toast
+    fn report(&mut self, action: &str, pid: i32, result: std::io::Result<()>) {
+        let result = result.map_err(|e| platform::explain_error(&e));
+        self.record(action, pid, result.clone());
+        if let Err(e) = result {
+            self.last_error = Some((format!("Failed to {} PID {}: {}", action, pid, e), Instant::now()));
+        }
+    }
+
+    // adds to the history panel; the name comes from the last refresh since
+    // a killed process is gone from the system by now
+    fn record(&mut self, action: &str, pid: i32, result: Result<(), String>) {
+        let name = self.procs.iter().find(|p| p.pid == pid).map(|p| p.name.clone()).unwrap_or_default();
+        let at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        if self.history.len() >= HISTORY_CAP {
+            self.history.pop_front();
+        }
+        self.history.push_back(ActionRecord {
+            at,
+            pid,
+            name,
+            action: action.trim_end_matches(" of").to_string(),
+            result,
+        });
+    }
+
+    // reaps launched processes that have exited; only the newest finished
+    // ones are kept so a script that launches a lot doesn't grow the list
+    fn poll_launched(&mut self) {
+        const KEEP_FINISHED: usize = 10;
+        for l in self.launched.iter_mut().filter(|l| l.exit.is_none()) {
+            if let Ok(Some(status)) = l.child.try_wait() {
+                l.exit = Some(status);
+            }
+        }
+        let finished = self.launched.iter().filter(|l| l.exit.is_some()).count();
+        let mut excess = finished.saturating_sub(KEEP_FINISHED);
+        self.launched.retain(|l| {
+            if excess > 0 && l.exit.is_some() {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    // critical processes need the stronger confirmation unless overridden
+    fn is_guarded(&self, pid: i32) -> bool {
+        !self.settings.allow_critical_kill
+            && self.procs.iter().find(|p| p.pid == pid).is_some_and(|p| platform::is_critical(pid, &p.name))
+    }
+
+    fn kill_checked(&mut self, verb: &'static str, pid: i32, action: GuardedAction) -> Command<Message> {
+        if self.is_guarded(pid) {
+            self.pending_critical = Some((pid, verb, action));
+            Command::none()
+        } else {
+            self.run_guarded(verb, pid, action)
+        }
+    }
+
+    fn run_guarded(&mut self, verb: &'static str, pid: i32, action: GuardedAction) -> Command<Message> {
+        match action {
+            GuardedAction::Run(f) => {
+                self.report(verb, pid, f(pid));
+                Command::none()
+            }
+            GuardedAction::Signal(signum) => {
+                self.report(verb, pid, platform::send_raw_signal(pid, signum));
+                Command::none()
+            }
+            GuardedAction::Restart => self.restart(pid),
+        }
+    }
+
+    // stops the process and relaunches its command line on a worker thread,
+    // RestartDone reports back
+    fn restart(&mut self, pid: i32) -> Command<Message> {
+        let Some(proc_) = self.monitor.system().process(sysinfo::Pid::from_u32(pid as u32)) else {
+            return Command::none();
+        };
+        // captured now, the process can't be asked once it's dead
+        let argv = proc_.cmd().to_vec();
+        let cwd = proc_.cwd().map(|d| d.to_path_buf());
+        let env: Vec<(String, String)> = proc_
+            .environ()
+            .iter()
+            .filter_map(|e| e.split_once('='))
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let start_time = proc_.start_time();
+        Command::perform(
+            async move {
+                let (tx, rx) = iced::futures::channel::oneshot::channel();
+                std::thread::spawn(move || {
+                    let result = platform::restart(pid, start_time, &argv, cwd.as_deref(), &env);
+                    let _ = tx.send(result.map_err(|e| platform::explain_error(&e)));
+                });
+                rx.await.unwrap_or_else(|_| Err("restart thread stopped".to_string()))
+            },
+            move |result| Message::RestartDone(pid, result),
+        )
+    }
+
+    // runs one action over every checked pid, then clears the selection
+    fn batch(&mut self, action: &str, f: ProcAction, guard_critical: bool) {
+        let mut pids: Vec<i32> = self.checked.drain().collect();
+        pids.sort_unstable();
+        self.run_batch(action, pids, f, guard_critical);
+    }
+
+    // keeps going past failures and reports them together, returns how many
+    // succeeded
+    fn run_batch(&mut self, action: &str, pids: Vec<i32>, f: ProcAction, guard_critical: bool) -> usize {
+        let failures: Vec<String> = pids
+            .iter()
+            .filter_map(|&pid| {
+                // one confirmation can't cover a whole batch, so these are skipped
+                if guard_critical && self.is_guarded(pid) {
+                    return Some(format!("PID {}: critical process, kill it on its own", pid));
+                }
+                let result = f(pid).map_err(|e| platform::explain_error(&e));
+                self.record(action, pid, result.clone());
+                result.err().map(|e| format!("PID {}: {}", pid, e))
+            })
+            .collect();
+        if !failures.is_empty() {
+            self.last_error = Some((
+                format!("Failed to {} {} of {}: {}", action, failures.len(), pids.len(), failures.join("; ")),
+                Instant::now(),
+            ));
+        }
+        pids.len() - failures.len()
+    }
+
+    // acts on processes that stayed over the watchdog threshold too long,
+    // the timer restarts whenever a process drops back under it
+    fn run_watchdog(&mut self, total_mem: u64) {
+        let wd = self.settings.watchdog;
+        if !wd.enabled {
+            return;
+        }
+        let own_pid = sysinfo::get_current_pid().map(|p| p.as_u32() as i32).ok();
+        let now = Instant::now();
+        let limit = Duration::from_secs(wd.duration_secs);
+
+        let over: HashSet<i32> = self
+            .procs
+            .iter()
+            .filter(|p| Some(p.pid) != own_pid)
+            // nobody is around to confirm, so the watchdog never touches these
+            .filter(|p| !platform::is_critical(p.pid, &p.name))
+            .filter(|p| match wd.metric {
+                WatchMetric::Cpu => p.cpu > wd.threshold as f32,
+                WatchMetric::Mem => {
+                    p.mem_bytes as f64 * 100.0 / total_mem as f64 > wd.threshold as f64
+                }
+            })
+            .map(|p| p.pid)
+            .collect();
+        self.breaches.retain(|pid, _| over.contains(pid));
+
+        let mut expired = Vec::new();
+        for pid in over {
+            let since = *self.breaches.entry(pid).or_insert(now);
+            if now.duration_since(since) >= limit {
+                expired.push(pid);
+            }
+        }
+
+        for pid in expired {
+            self.breaches.remove(&pid);
+            let name = self
+                .procs
+                .iter()
+                .find(|p| p.pid == pid)
+                .map(|p| p.name.clone())
+                .unwrap_or_default();
+            let result = match wd.action {
+                WatchAction::Kill => platform::kill(pid),
+                WatchAction::Suspend => platform::suspend(pid),
+            }
+            .map_err(|e| platform::explain_error(&e));
+            self.record(&format!("watchdog {}", wd.action), pid, result.clone());
+            let line = match &result {
+                Ok(()) => format!(
+                    "Watchdog: {} {} (PID {}) after {}s over {}% {}",
+                    wd.action, name, pid, wd.duration_secs, wd.threshold, wd.metric
+                ),
+                Err(e) => format!("Watchdog: failed to {} {} (PID {}): {}", wd.action, name, pid, e),
+            };
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs());
+            let outcome = match &result {
+                Ok(()) => "ok".to_string(),
+                Err(e) => format!("failed: {}", e),
+            };
+            self.append_alert_log(&format!(
+                "{} watchdog action={} pid={} name={} rule=\"{} over {}% for {}s\" result={}",
+                fmt_utc(now), wd.action, pid, name, wd.metric, wd.threshold, wd.duration_secs, outcome
+            ));
+            self.status_msg = Some(line);
+        }
+    }
+
+    // interfaces feeding the net graphs, per the picker and loopback toggle
+    fn net_interface_included(&self, name: &str) -> bool {
+        match &self.settings.net_interface {
+            Some(selected) => selected == name,
+            None => !(self.settings.exclude_loopback && is_loopback(name)),
+        }
+    }
+
+    fn selected_net_rate(&self) -> (f32, f32) {
+        self.net_rates
+            .iter()
+            .filter(|r| self.net_interface_included(&r.name))
+            .fold((0.0, 0.0), |(rx, tx), r| (rx + r.rx_bps, tx + r.tx_bps))
+    }
+
+    // hands the smoothing settings to the monitor
+    fn sync_smoothing(&mut self) {
+        self.monitor.set_cpu_smoothing(self.settings.smooth_cpu.then_some(self.settings.cpu_alpha));
+    }
+
     fn refresh_now(&mut self) {
-        self.sys.refresh_all();
-        self.networks.refresh();
+        self.monitor.set_extra_reads(ExtraReads::for_settings(&self.settings, self.selected));
+        // reuse last tick's buffer instead of allocating a fresh one
+        let snap = self.monitor.sample_into(std::mem::take(&mut self.procs));
+        self.stats = snap.stats;
+        self.net_rates = snap.net_rates;
+        self.disks = snap.disks;
+        if snap.dt.is_finite() && snap.dt > 0.0 {
+            self.sample_secs = Some(ema(self.sample_secs, snap.dt, SAMPLE_ALPHA));
+        }
+        let total_cpu = self.stats.cpu_percent;
+        let total_mem = self.stats.total_mem;
+        let mem_pct = self.stats.mem_percent();
+        let swap_pct = self.stats.swap_percent();
+        let (net_rx_bps, net_tx_bps) = self.selected_net_rate();
+
+        let due = if self.graphs_paused { 0 } else { self.graph_clock.due(Instant::now()) };
+        for _ in 0..due {
+            self.graphs.cpu.push(total_cpu);
+            self.graphs.mem.push(mem_pct);
+            self.graphs.swap.push(swap_pct);
+            // a long gap would plot its average as if it just happened
+            if snap.rates_valid {
+                self.graphs.net_rx.push(net_rx_bps);
+                self.graphs.net_tx.push(net_tx_bps);
+                self.graphs.disk_read.push(snap.disk_read_bps);
+                self.graphs.disk_write.push(snap.disk_write_bps);
+            }
+            if let Some([one, _, _]) = snap.stats.load_avg {
+                self.graphs.load.push(one);
+            }
+            if let Some(t) = snap.temperature {
+                self.graphs.temp.push(t);
+            }
+            if let Some(sample) = snap.gpu {
+                self.graphs.gpu.push(sample.util_percent);
+                self.graphs.vram.push(sample.vram_percent);
+            }
+        }
 
+        for p in &snap.rows {
+            self.cpu_history.entry(p.pid).or_default().push_capped(p.cpu, PROC_GRAPH_POINTS);
+            self.mem_history.entry(p.pid).or_default().push_capped(p.mem_bytes as f32, LEAK_SAMPLES);
+        }
+        prune_row_history(&mut self.cpu_history, &snap.rows);
+        prune_row_history(&mut self.mem_history, &snap.rows);
         let now = Instant::now();
-        let dt = now.duration_since(self.last_ts).as_secs_f32().max(0.001);
-        self.last_ts = now;
-
-        let total_cpu = self.sys.global_cpu_info().cpu_usage();
-        let used_mem = self.sys.used_memory();
-        let total_mem = self.sys.total_memory().max(1);
-        let mem_pct = (used_mem as f32) * 100.0 / (total_mem as f32);
-
-        let (disk_r_total, disk_w_total) = total_disk_bytes(&self.sys);
-        let disk_r_bps = bytes_per_sec(self.last_disk.0, disk_r_total, dt);
-        let disk_w_bps = bytes_per_sec(self.last_disk.1, disk_w_total, dt);
-        self.last_disk = (disk_r_total, disk_w_total);
-
-        let (net_rx_total, net_tx_total) = total_net_bytes(&self.networks);
-        let net_rx_bps = bytes_per_sec(self.last_net.0, net_rx_total, dt);
-        let net_tx_bps = bytes_per_sec(self.last_net.1, net_tx_total, dt);
-        self.last_net = (net_rx_total, net_tx_total);
-
-        self.graphs.cpu.push(total_cpu);
-        self.graphs.mem.push(mem_pct);
-        self.graphs.disk_read.push(disk_r_bps as f32);
-        self.graphs.disk_write.push(disk_w_bps as f32);
-        self.graphs.net_rx.push(net_rx_bps as f32);
-        self.graphs.net_tx.push(net_tx_bps as f32);
-
-        let mut rows: Vec<ProcRow> = Vec::with_capacity(self.sys.processes().len());
-        for (pid, proc_) in self.sys.processes() {
-            let pid_i32 = pid.as_u32() as i32;
-            let name = proc_.name().to_string();
-            let cpu = proc_.cpu_usage();
-            let mem_bytes = proc_.memory();
-            let io = proc_.disk_usage();
-            let prev = self
-                .last_io
-                .get(&pid_i32)
-                .copied()
-                .unwrap_or(IoSnapshot {
-                    read: io.total_read_bytes,
-                    write: io.total_written_bytes,
-                });
-            let read_bps = bytes_per_sec(prev.read, io.total_read_bytes, dt) as u64;
-            let write_bps = bytes_per_sec(prev.write, io.total_written_bytes, dt) as u64;
-            self.last_io.insert(
-                pid_i32,
-                IoSnapshot {
-                    read: io.total_read_bytes,
-                    write: io.total_written_bytes,
-                },
-            );
-
-            rows.push(ProcRow {
-                pid: pid_i32,
-                name,
-                cpu,
-                mem_bytes,
-                read_bps,
-                write_bps,
-            });
-        }
-        self.procs = rows;
-
-        self.suggestions = make_suggestions(
+        let live = track_new_pids(self.seen_pids.as_ref(), &snap.rows, &mut self.new_since, now);
+        self.seen_pids = Some(live);
+        self.procs = snap.rows;
+        #[cfg(all(feature = "procnet", target_os = "linux"))]
+        procnet::attribute(&mut self.procs, &snap.sockets, net_rx_bps, net_tx_bps);
+        self.run_watchdog(total_mem);
+
+        // keep the details pane live, and drop it when the process exits
+        self.details = self.selected.and_then(|pid| process_details(self.monitor.system(), pid));
+        if self.details.is_none() {
+            self.selected = None;
+        }
+        let procs = &self.procs;
+        self.checked.retain(|pid| procs.iter().any(|p| p.pid == *pid));
+
+        let mut suggestions = make_suggestions(
             &self.procs,
             if self.settings.alerts_on_cpu { total_cpu } else { 0.0 },
             if self.settings.alerts_on_mem { mem_pct } else { 0.0 },
+            &self.settings.thresholds,
+            &self.mem_history,
+            TICK.as_secs_f32(),
+            &self.disks,
         );
+        self.log_new_alerts(&suggestions, total_cpu, mem_pct);
+        self.notify_alerts(&suggestions);
+        // forget dismissals whose condition has cleared so they can re-trigger
+        self.dismissed.retain(|k| suggestions.iter().any(|s| &s.key == k));
+        suggestions.retain(|s| !self.dismissed.contains(&s.key));
+        self.suggestions = suggestions;
+
+        #[cfg(feature = "metrics")]
+        self.publish_metrics(mem_pct, swap_pct);
+    }
+
+    // hands the server a fresh snapshot; if it is mid-read we skip this tick
+    // rather than wait on the lock
+    #[cfg(feature = "metrics")]
+    fn publish_metrics(&self, mem_pct: f32, swap_pct: f32) {
+        let Some(shared) = &self.metrics else { return };
+        let (top_cpu, top_mem) = metrics::top_processes(&self.procs);
+        let snap = MetricsSnapshot {
+            cpu_percent: self.stats.cpu_percent,
+            mem_percent: mem_pct,
+            swap_percent: swap_pct,
+            net: self.net_rates.clone(),
+            top_cpu,
+            top_mem,
+        };
+        if let Ok(mut current) = shared.try_lock() {
+            *current = snap;
+        }
     }
 
-    fn filtered_sorted_rows(&self) -> Vec<ProcRow> {
-        let mut v: Vec<ProcRow> = self.procs.iter().cloned().collect();
-        let filt = self.settings.filter.trim().to_lowercase();
-        if !filt.is_empty() {
-            v.retain(|p| {
-                p.name.to_lowercase().contains(&filt) || p.pid.to_string().contains(&filt)
-            });
-        }
-        v.sort_by(|a, b| {
-            use std::cmp::Ordering::*;
-            let ord = match self.settings.sort_key {
-                SortKey::Pid => a.pid.cmp(&b.pid),
-                SortKey::Name => a.name.cmp(&b.name),
-                SortKey::Cpu => a.cpu.partial_cmp(&b.cpu).unwrap_or(Equal),
-                SortKey::Mem => a.mem_bytes.cmp(&b.mem_bytes),
-                SortKey::Read => a.read_bps.cmp(&b.read_bps),
-                SortKey::Write => a.write_bps.cmp(&b.write_bps),
+    // writes one line per cpu/mem alert when it starts firing, not every tick
+    fn log_new_alerts(&mut self, suggestions: &[Suggestion], total_cpu: f32, mem_pct: f32) {
+        let firing: HashSet<String> = suggestions
+            .iter()
+            .filter(|s| s.key.starts_with("cpu:") || s.key.starts_with("mem:"))
+            .map(|s| s.key.clone())
+            .collect();
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let new_alerts = if self.settings.alert_log { firing.difference(&self.firing_alerts).collect() } else { Vec::new() };
+        for key in new_alerts {
+            let Some((metric, pid)) = key.split_once(':') else { continue };
+            let Some(p) = self.procs.iter().find(|p| p.pid.to_string() == pid) else {
+                continue;
+            };
+            let line = if metric == "cpu" {
+                format!(
+                    "{} cpu total={:.1}% pid={} name={} process_cpu={:.1}%",
+                    fmt_utc(now), total_cpu, p.pid, p.name, p.cpu
+                )
+            } else {
+                format!(
+                    "{} mem total={:.1}% pid={} name={} process_mem={}",
+                    fmt_utc(now), mem_pct, p.pid, p.name, fmt_bytes(p.mem_bytes)
+                )
             };
-            match self.settings.sort_dir {
-                SortDir::Asc => ord,
-                SortDir::Desc => ord.reverse(),
+            self.append_alert_log(&line);
+        }
+        self.firing_alerts = firing;
+    }
+
+    // one line to the alert log, when it's switched on
+    fn append_alert_log(&self, line: &str) {
+        if !self.settings.alert_log {
+            return;
+        }
+        let Some(path) = config::alert_log_path() else { return };
+        let max_bytes = self.settings.alert_log_max_kb.max(1) * 1024;
+        if let Err(e) = alert_log::append_line(&path, max_bytes, line) {
+            eprintln!("failed to write alert log: {}", e);
+        }
+    }
+
+    // one desktop notification per metric per cooldown window
+    fn notify_alerts(&mut self, suggestions: &[Suggestion]) {
+        if !self.settings.notify {
+            return;
+        }
+        let cooldown = Duration::from_secs(self.settings.notify_cooldown_secs);
+        for metric in ["cpu", "mem"] {
+            let Some(s) = suggestions.iter().find(|s| s.key.starts_with(metric)) else {
+                continue;
+            };
+            if self.last_notified.get(metric).is_some_and(|at| at.elapsed() < cooldown) {
+                continue;
             }
+            self.last_notified.insert(metric, Instant::now());
+            notify::send(s.title.clone(), s.detail.clone());
+        }
+    }
+
+    // compiles the filter as a case-insensitive regex when regex mode is on
+    fn compile_filter(&mut self) {
+        self.filter_re = filter_regex(&self.settings);
+    }
+
+    // regex mode is on but the pattern didn't compile
+    fn filter_invalid(&self) -> bool {
+        self.settings.filter_regex && !self.settings.filter.trim().is_empty() && self.filter_re.is_none()
+    }
+
+    // part of a name matched by the current filter, for highlighting
+    fn match_range(&self, name: &str) -> Option<(usize, usize)> {
+        match &self.filter_re {
+            Some(re) => re.find(name).filter(|m| !m.is_empty()).map(|m| (m.start(), m.end())),
+            None => find_ignore_case(name, self.settings.filter.trim()),
+        }
+    }
+
+    // the table body, either one row per process or grouped by name
+    fn table_rows(&self) -> Vec<Element<'_, Message>> {
+        let rows = self.filtered_sorted_rows();
+        let plain_row = |p: &ProcRow| -> Element<'_, Message> {
+            let selected = self.selected == Some(p.pid);
+            let checked = self.checked.contains(&p.pid);
+            let highlight = self.match_range(&p.name);
+            let history = self.cpu_history.get(&p.pid);
+            let anchor = self.context_menu.filter(|(pid, _)| *pid == p.pid).map(|(_, at)| at);
+            let flags = RowFlags {
+                selected,
+                checked,
+                actions_open: anchor == Some(MenuAnchor::Actions),
+                compact: self.settings.compact,
+                new_fade: new_fade(self.new_since.get(&p.pid).copied(), Instant::now()),
+            };
+            let thresholds = &self.settings.thresholds;
+            let cells = process_row(p, &self.settings.columns, flags, history, highlight, thresholds);
+            let row = mouse_area(cells).on_press(Message::SelectProcess(p.pid));
+            let menu = match anchor {
+                Some(MenuAnchor::Cursor(at)) => Some((at, process_menu(p))),
+                _ => None,
+            };
+            let pid = p.pid;
+            context_area(row, menu, move |at| Message::ShowContextMenu(pid, at), Message::CloseContextMenu).into()
+        };
+        if !self.settings.group_by_name {
+            return rows.into_iter().map(plain_row).collect();
+        }
+
+        let mut out = Vec::new();
+        for g in group_by_name(rows, &self.settings) {
+            if g.members.len() == 1 {
+                out.push(plain_row(g.members[0]));
+                continue;
+            }
+            let expanded = self.expanded_groups.contains(&g.summary.name);
+            let highlight = self.match_range(&g.summary.name);
+            let columns = &self.settings.columns;
+            let (thresholds, compact) = (&self.settings.thresholds, self.settings.compact);
+            let cells = group_row(&g.summary, g.members.len(), expanded, columns, highlight, thresholds, compact);
+            out.push(mouse_area(cells).on_press(Message::ToggleGroup(g.summary.name.clone())).into());
+            if expanded {
+                out.extend(g.members.into_iter().map(plain_row));
+            }
+        }
+        out
+    }
+
+    fn graph_spec(&self, id: GraphId) -> GraphSpec<'_> {
+        let net_fmt = if self.settings.net_bits { fmt_bits_per_sec } else { fmt_rate };
+        let (label, series, color, fmt_value, max_scale): (_, _, _, fn(f32) -> String, _) = match id {
+            GraphId::Cpu => ("CPU", &self.graphs.cpu, Color::from_rgb(1.0, 0.3, 0.3), fmt_percent, Some(100.0)),
+            GraphId::Mem => ("Mem", &self.graphs.mem, Color::from_rgb(0.3, 1.0, 0.3), fmt_percent, Some(100.0)),
+            GraphId::Swap => ("Swap", &self.graphs.swap, Color::from_rgb(0.9, 0.9, 0.3), fmt_percent, Some(100.0)),
+            GraphId::DiskRead => ("Disk R", &self.graphs.disk_read, Color::from_rgb(0.3, 0.8, 1.0), fmt_rate, None),
+            GraphId::DiskWrite => ("Disk W", &self.graphs.disk_write, Color::from_rgb(1.0, 0.8, 0.3), fmt_rate, None),
+            GraphId::NetRx => ("Net RX", &self.graphs.net_rx, Color::from_rgb(1.0, 0.5, 1.0), net_fmt, None),
+            GraphId::NetTx => ("Net TX", &self.graphs.net_tx, Color::from_rgb(0.8, 0.3, 1.0), net_fmt, None),
+            GraphId::Temp => ("Temp °C", &self.graphs.temp, Color::from_rgb(1.0, 0.6, 0.2), fmt_celsius, None),
+            GraphId::Gpu => ("GPU", &self.graphs.gpu, Color::from_rgb(0.4, 1.0, 0.8), fmt_percent, Some(100.0)),
+            GraphId::Vram => ("VRAM", &self.graphs.vram, Color::from_rgb(0.3, 0.7, 0.6), fmt_percent, Some(100.0)),
+            GraphId::Load => ("Load 1m", &self.graphs.load, Color::from_rgb(0.6, 0.75, 1.0), fmt_load, None),
+        };
+        GraphSpec { id, label, series, color, fmt_value, max_scale }
+    }
+
+    fn filtered_sorted_rows(&self) -> Vec<&ProcRow> {
+        filter_sort_rows(&self.procs, &self.settings, self.filter_re.as_ref())
+    }
+}
+
+// checks the start options up front so a typo reads as what it is rather
+// than a bare "No such file or directory" from spawn
+fn start_command(req: &StartRequest) -> Result<Option<std::process::Child>, String> {
+    let env = parse_env(&req.env)?;
+    let cwd = req.cwd.trim();
+    let cwd = if cwd.is_empty() { None } else { Some(std::path::Path::new(cwd)) };
+    if let Some(dir) = cwd {
+        if !dir.is_dir() {
+            return Err(format!("{} is not a directory", dir.display()));
+        }
+    }
+    platform::start(&req.cmd, cwd, &env, req.detached).map_err(|e| e.to_string())
+}
+
+// the filter box as a regex, None when regex mode is off, the box is empty
+// or the pattern doesn't compile
+pub fn filter_regex(settings: &SettingsModel) -> Option<Regex> {
+    let pattern = settings.filter.trim();
+    if settings.filter_regex && !pattern.is_empty() {
+        RegexBuilder::new(pattern).case_insensitive(true).build().ok()
+    } else {
+        None
+    }
+}
+
+// `pid:1234,5678` in the filter box lists exact pids; entries that aren't a
+// pid are skipped. None for any other filter, and for a list with nothing
+// valid in it yet so the table doesn't empty out while it's being typed
+pub fn parse_pid_filter(filter: &str) -> Option<HashSet<i32>> {
+    let filter = filter.trim();
+    let list = filter.get(..4).filter(|p| p.eq_ignore_ascii_case("pid:")).map(|_| &filter[4..])?;
+    let pids: HashSet<i32> = list.split(',').filter_map(|s| s.trim().parse().ok()).collect();
+    (!pids.is_empty()).then_some(pids)
+}
+
+// filters and sorts rows for display, borrowing them rather than cloning
+pub fn filter_sort_rows<'a>(
+    rows: &'a [ProcRow],
+    settings: &SettingsModel,
+    filter_re: Option<&Regex>,
+) -> Vec<&'a ProcRow> {
+    let mut v: Vec<&ProcRow> = rows.iter().collect();
+    let filt = settings.filter.trim().to_lowercase();
+    if let Some(pids) = parse_pid_filter(&filt) {
+        v.retain(|p| pids.contains(&p.pid));
+    } else if let Some(re) = filter_re {
+        v.retain(|p| re.is_match(&p.name));
+    } else if !filt.is_empty() {
+        // substring match, also the fallback for an invalid regex
+        v.retain(|p| {
+            p.name.to_lowercase().contains(&filt) || p.pid.to_string().contains(&filt)
         });
-        v
     }
+    // numeric minimums and ownership narrow the text match further
+    v.retain(|p| {
+        p.cpu >= settings.min_cpu && p.mem_bytes >= settings.min_mem && (p.mine || !settings.mine_only)
+    });
+    v.sort_by(|a, b| compare_rows(a, b, settings));
+    // after sorting, so "top 50" follows whichever column is sorted
+    if let RowLimit::Top(n) = settings.row_limit {
+        v.truncate(n);
+    }
+    v
+}
+
+fn parse_min(s: &str) -> Result<u64, std::num::ParseIntError> {
+    let s = s.trim();
+    if s.is_empty() {
+        Ok(0)
+    } else {
+        s.parse()
+    }
+}
+
+// ordering for the current sort column and direction; ties fall back to
+// ascending pid so idle rows don't trade places between refreshes
+fn compare_rows(a: &ProcRow, b: &ProcRow, settings: &SettingsModel) -> std::cmp::Ordering {
+    use std::cmp::Ordering::*;
+    let ord = match settings.sort_key {
+        SortKey::Pid => a.pid.cmp(&b.pid),
+        SortKey::Name => a.name.cmp(&b.name),
+        SortKey::User => a.user.cmp(&b.user),
+        SortKey::Priority => a.priority.cmp(&b.priority),
+        SortKey::Cpu => a.cpu.partial_cmp(&b.cpu).unwrap_or(Equal),
+        SortKey::Mem => a.mem_bytes.cmp(&b.mem_bytes),
+        SortKey::Swap => a.swap_bytes.cmp(&b.swap_bytes),
+        SortKey::Read => a.read_bps.cmp(&b.read_bps),
+        SortKey::Write => a.write_bps.cmp(&b.write_bps),
+        SortKey::Threads => a.threads.cmp(&b.threads),
+        SortKey::OpenFiles => a.open_files.cmp(&b.open_files),
+        SortKey::ReadTotal => a.read_total.cmp(&b.read_total),
+        SortKey::WriteTotal => a.write_total.cmp(&b.write_total),
+        SortKey::Uptime => a.uptime_secs.cmp(&b.uptime_secs),
+        SortKey::NetRx => a.net_rx_bps.cmp(&b.net_rx_bps),
+        SortKey::NetTx => a.net_tx_bps.cmp(&b.net_tx_bps),
+    };
+    let ord = match settings.sort_dir {
+        SortDir::Asc => ord,
+        SortDir::Desc => ord.reverse(),
+    };
+    ord.then(a.pid.cmp(&b.pid))
+}
+
+// adds what is known, None only when neither side is
+fn sum_known<T: std::ops::Add<Output = T>>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a + b),
+        (x, None) | (None, x) => x,
+    }
+}
+
+// collapses already filtered and sorted rows into one group per name, each
+// group summed into a summary row and sorted the same way as plain rows
+pub fn group_by_name<'a>(rows: Vec<&'a ProcRow>, settings: &SettingsModel) -> Vec<ProcGroup<'a>> {
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let mut groups: Vec<ProcGroup<'a>> = Vec::new();
+    for p in rows {
+        match index.get(p.name.as_str()) {
+            Some(&i) => {
+                let g = &mut groups[i];
+                let s = &mut g.summary;
+                s.cpu += p.cpu;
+                s.mem_bytes += p.mem_bytes;
+                s.mem_percent += p.mem_percent;
+                s.swap_bytes = sum_known(s.swap_bytes, p.swap_bytes);
+                s.read_bps = sum_known(s.read_bps, p.read_bps);
+                s.write_bps = sum_known(s.write_bps, p.write_bps);
+                s.threads += p.threads;
+                s.open_files = sum_known(s.open_files, p.open_files);
+                s.read_total = sum_known(s.read_total, p.read_total);
+                s.write_total = sum_known(s.write_total, p.write_total);
+                s.start_time = s.start_time.min(p.start_time);
+                s.uptime_secs = s.uptime_secs.max(p.uptime_secs);
+                s.net_rx_bps = sum_known(s.net_rx_bps, p.net_rx_bps);
+                s.net_tx_bps = sum_known(s.net_tx_bps, p.net_tx_bps);
+                if s.user != p.user {
+                    s.user = "*".to_string();
+                }
+                if s.priority != p.priority {
+                    s.priority = None;
+                }
+                g.members.push(p);
+            }
+            None => {
+                index.insert(p.name.as_str(), groups.len());
+                groups.push(ProcGroup { summary: p.clone(), members: vec![p] });
+            }
+        }
+    }
+    groups.sort_by(|a, b| compare_rows(&a.summary, &b.summary, settings));
+    groups
 }
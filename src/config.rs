@@ -0,0 +1,47 @@
+// This file loads and saves the persisted settings
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::models::SettingsModel;
+
+// per-user procdeck directory
+fn config_dir() -> Option<PathBuf> {
+    #[cfg(target_family = "windows")]
+    let base = std::env::var_os("APPDATA").map(PathBuf::from);
+    #[cfg(target_family = "unix")]
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")));
+
+    base.map(|dir| dir.join("procdeck"))
+}
+
+// settings.json inside the per-user config directory
+pub fn config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("settings.json"))
+}
+
+// alerts.log next to the settings
+pub fn alert_log_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("alerts.log"))
+}
+
+// falls back to defaults when the file is missing or unreadable
+pub fn load_settings() -> SettingsModel {
+    config_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(settings: &SettingsModel) -> io::Result<()> {
+    let path = config_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(io::Error::other)?;
+    fs::write(path, json)
+}
@@ -0,0 +1,247 @@
+// This file is a wrapper widget that reports right clicks and can float a
+// menu over everything else, anchored where the click happened
+
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::widget::{self, Tree, Widget};
+use iced::advanced::{overlay, renderer, Clipboard, Shell};
+use iced::{event, mouse, Element, Event, Length, Point, Rectangle, Renderer, Size, Theme, Vector};
+
+pub struct ContextArea<'a, Message> {
+    content: Element<'a, Message>,
+    menu: Option<(Point, Element<'a, Message>)>, // anchor is relative to the area
+    on_right_press: Box<dyn Fn(Point) -> Message + 'a>,
+    on_dismiss: Message,
+}
+
+// menu is Some while this area's menu is open
+pub fn context_area<'a, Message>(
+    content: impl Into<Element<'a, Message>>,
+    menu: Option<(Point, Element<'a, Message>)>,
+    on_right_press: impl Fn(Point) -> Message + 'a,
+    on_dismiss: Message,
+) -> ContextArea<'a, Message> {
+    ContextArea {
+        content: content.into(),
+        menu,
+        on_right_press: Box::new(on_right_press),
+        on_dismiss,
+    }
+}
+
+impl<'a, Message: Clone> Widget<Message, Theme, Renderer> for ContextArea<'a, Message> {
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        let mut children = vec![Tree::new(&self.content)];
+        if let Some((_, menu)) = &self.menu {
+            children.push(Tree::new(menu));
+        }
+        children
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        match &self.menu {
+            Some((_, menu)) => tree.diff_children(&[&self.content, menu]),
+            None => tree.diff_children(&[&self.content]),
+        }
+    }
+
+    fn layout(&self, tree: &mut Tree, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content.as_widget().layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content
+            .as_widget()
+            .draw(&tree.children[0], renderer, theme, style, layout, cursor, viewport);
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn widget::Operation<Message>,
+    ) {
+        self.content
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        if let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)) = event {
+            if let Some(pos) = cursor.position_in(layout.bounds()) {
+                shell.publish((self.on_right_press)(pos));
+                return event::Status::Captured;
+            }
+        }
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content
+            .as_widget()
+            .mouse_interaction(&tree.children[0], layout, cursor, viewport, renderer)
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        let (content_tree, rest) = tree.children.split_at_mut(1);
+        match &mut self.menu {
+            Some((anchor, menu)) => Some(overlay::Element::new(Box::new(MenuOverlay {
+                // window coordinates, scrolling included
+                anchor: layout.position() + translation + Vector::new(anchor.x, anchor.y),
+                menu,
+                tree: &mut rest[0],
+                on_dismiss: self.on_dismiss.clone(),
+            }))),
+            None => self
+                .content
+                .as_widget_mut()
+                .overlay(&mut content_tree[0], layout, renderer, translation),
+        }
+    }
+}
+
+impl<'a, Message: Clone + 'a> From<ContextArea<'a, Message>> for Element<'a, Message> {
+    fn from(area: ContextArea<'a, Message>) -> Self {
+        Element::new(area)
+    }
+}
+
+struct MenuOverlay<'a, 'b, Message> {
+    anchor: Point,
+    menu: &'b mut Element<'a, Message>,
+    tree: &'b mut Tree,
+    on_dismiss: Message,
+}
+
+impl<'a, 'b, Message: Clone> overlay::Overlay<Message, Theme, Renderer> for MenuOverlay<'a, 'b, Message> {
+    // covers the whole window so a click anywhere else closes the menu
+    fn layout(&mut self, renderer: &Renderer, bounds: Size) -> layout::Node {
+        let limits = layout::Limits::new(Size::ZERO, bounds);
+        let menu = self.menu.as_widget().layout(self.tree, renderer, &limits);
+        let size = menu.size();
+        // flip left/up when the menu would run off the window edge
+        let x = if self.anchor.x + size.width > bounds.width {
+            (self.anchor.x - size.width).max(0.0)
+        } else {
+            self.anchor.x
+        };
+        let y = if self.anchor.y + size.height > bounds.height {
+            (self.anchor.y - size.height).max(0.0)
+        } else {
+            self.anchor.y
+        };
+        layout::Node::with_children(bounds, vec![menu.move_to(Point::new(x, y))])
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+    ) {
+        let menu_layout = layout.children().next().expect("menu layout");
+        self.menu
+            .as_widget()
+            .draw(self.tree, renderer, theme, style, menu_layout, cursor, &layout.bounds());
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        let menu_layout = layout.children().next().expect("menu layout");
+        if let Event::Mouse(mouse::Event::ButtonPressed(_)) = event {
+            if !cursor.is_over(menu_layout.bounds()) {
+                shell.publish(self.on_dismiss.clone());
+                return event::Status::Captured;
+            }
+        }
+        self.menu.as_widget_mut().on_event(
+            self.tree,
+            event,
+            menu_layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            &layout.bounds(),
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let menu_layout = layout.children().next().expect("menu layout");
+        self.menu
+            .as_widget()
+            .mouse_interaction(self.tree, menu_layout, cursor, viewport, renderer)
+    }
+
+    // nested overlays, e.g. a pick_list inside the menu
+    fn overlay<'c>(
+        &'c mut self,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+    ) -> Option<overlay::Element<'c, Message, Theme, Renderer>> {
+        let menu_layout = layout.children().next().expect("menu layout");
+        self.menu
+            .as_widget_mut()
+            .overlay(self.tree, menu_layout, renderer, Vector::ZERO)
+    }
+}
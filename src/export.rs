@@ -0,0 +1,135 @@
+// This file writes process snapshots to disk
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::models::{GraphSeries, ProcRow, Suggestion, SystemGraphs, SystemStats};
+
+// bumped whenever a field is renamed or removed from the json snapshot
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+// quote a csv field when it contains a separator, quote or newline
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+// timestamped file in the home directory (or current dir as fallback)
+pub fn default_export_path(ext: &str) -> PathBuf {
+    let dir = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    dir.join(format!("procdeck-{}.{}", ts, ext))
+}
+
+pub fn write_csv<W: Write>(out: &mut W, rows: &[&ProcRow]) -> io::Result<()> {
+    writeln!(out, "PID,Name,CPU%,Memory,Read/s,Write/s")?;
+    for p in rows {
+        writeln!(
+            out,
+            "{},{},{:.1},{},{},{}",
+            p.pid,
+            csv_field(&p.name),
+            p.cpu,
+            p.mem_bytes,
+            // left empty when the process's I/O couldn't be read
+            p.read_bps.map_or(String::new(), |b| b.to_string()),
+            p.write_bps.map_or(String::new(), |b| b.to_string())
+        )?;
+    }
+    Ok(())
+}
+
+pub fn export_csv(rows: &[&ProcRow], path: &Path) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_csv(&mut out, rows)?;
+    out.flush()
+}
+
+// newest point of every system graph, None where nothing was sampled yet
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct LatestValues {
+    pub cpu_percent: Option<f32>,
+    pub mem_percent: Option<f32>,
+    pub swap_percent: Option<f32>,
+    pub disk_read_bps: Option<f32>,
+    pub disk_write_bps: Option<f32>,
+    pub net_rx_bps: Option<f32>,
+    pub net_tx_bps: Option<f32>,
+    pub temp_celsius: Option<f32>,
+    pub gpu_percent: Option<f32>,
+    pub vram_percent: Option<f32>,
+}
+
+impl From<&SystemGraphs> for LatestValues {
+    fn from(g: &SystemGraphs) -> Self {
+        let last = |s: &GraphSeries| s.points.back().copied();
+        LatestValues {
+            cpu_percent: last(&g.cpu),
+            mem_percent: last(&g.mem),
+            swap_percent: last(&g.swap),
+            disk_read_bps: last(&g.disk_read),
+            disk_write_bps: last(&g.disk_write),
+            net_rx_bps: last(&g.net_rx),
+            net_tx_bps: last(&g.net_tx),
+            temp_celsius: last(&g.temp),
+            gpu_percent: last(&g.gpu),
+            vram_percent: last(&g.vram),
+        }
+    }
+}
+
+// the whole picture in one document: totals, graphs, every row and the
+// active suggestions
+#[derive(Debug, serde::Serialize)]
+pub struct JsonSnapshot<'a> {
+    pub schema_version: u32,
+    pub timestamp: u64, // unix seconds
+    pub stats: &'a SystemStats,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub graphs: Option<LatestValues>,
+    pub processes: Vec<&'a ProcRow>,
+    pub suggestions: &'a [Suggestion],
+}
+
+impl<'a> JsonSnapshot<'a> {
+    pub fn new(
+        stats: &'a SystemStats,
+        graphs: Option<&SystemGraphs>,
+        processes: Vec<&'a ProcRow>,
+        suggestions: &'a [Suggestion],
+    ) -> Self {
+        JsonSnapshot {
+            schema_version: JSON_SCHEMA_VERSION,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            stats,
+            graphs: graphs.map(LatestValues::from),
+            processes,
+            suggestions,
+        }
+    }
+}
+
+pub fn write_json<W: Write>(out: &mut W, snapshot: &JsonSnapshot) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, snapshot)?;
+    writeln!(out)
+}
+
+pub fn export_json(snapshot: &JsonSnapshot, path: &Path) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_json(&mut out, snapshot)?;
+    out.flush()
+}
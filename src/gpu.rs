@@ -0,0 +1,65 @@
+// This file samples GPU load and VRAM through NVML (cargo feature "gpu")
+
+// averaged across all GPUs
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GpuSample {
+    pub util_percent: f32,
+    pub vram_percent: f32,
+}
+
+#[cfg(feature = "gpu")]
+pub struct GpuMonitor {
+    nvml: nvml_wrapper::Nvml,
+    count: u32,
+}
+
+// Nvml itself has no Debug impl
+#[cfg(feature = "gpu")]
+impl std::fmt::Debug for GpuMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GpuMonitor").field("count", &self.count).finish()
+    }
+}
+
+#[cfg(feature = "gpu")]
+impl GpuMonitor {
+    // None when the NVIDIA driver isn't loaded or there are no devices
+    pub fn new() -> Option<Self> {
+        let nvml = nvml_wrapper::Nvml::init().ok()?;
+        let count = nvml.device_count().ok().filter(|&c| c > 0)?;
+        Some(GpuMonitor { nvml, count })
+    }
+
+    pub fn sample(&self) -> Option<GpuSample> {
+        let mut util = 0.0;
+        let mut used = 0u64;
+        let mut total = 0u64;
+        for i in 0..self.count {
+            let device = self.nvml.device_by_index(i).ok()?;
+            util += device.utilization_rates().ok()?.gpu as f32;
+            let mem = device.memory_info().ok()?;
+            used += mem.used;
+            total += mem.total;
+        }
+        Some(GpuSample {
+            util_percent: util / self.count as f32,
+            vram_percent: if total == 0 { 0.0 } else { used as f32 * 100.0 / total as f32 },
+        })
+    }
+}
+
+// without the feature there is never a GPU to show
+#[cfg(not(feature = "gpu"))]
+#[derive(Debug)]
+pub struct GpuMonitor;
+
+#[cfg(not(feature = "gpu"))]
+impl GpuMonitor {
+    pub fn new() -> Option<Self> {
+        None
+    }
+
+    pub fn sample(&self) -> Option<GpuSample> {
+        None
+    }
+}
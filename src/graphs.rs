@@ -1,16 +1,205 @@
 // This file creates small line charts for graphs
 use std::collections::VecDeque;
-use iced::{Color, Element, Length, Rectangle, Theme};
-use iced::widget::{column, container, text};
+use iced::{Alignment, Color, Element, Length, Rectangle, Theme};
+use iced::widget::{button, column, container, mouse_area, row, text, Space};
 use iced_widget::canvas::{self, Frame, Stroke};
-use crate::models::{GraphSeries, Message};
+use crate::models::{GraphId, GraphSeries, Message, PROC_GRAPH_POINTS};
+use crate::styles::{is_dark, tinted};
+use crate::util::fmt_duration;
 
-// creates a label graph widget
-pub fn sparkline<'a>(label: &str, series: &'a GraphSeries, color: Color) -> Element<'a, Message> {
-    struct Plot<'a>(&'a VecDeque<f32>, Color);
+// everything a graph card needs to draw one metric; max_scale pins the top
+// of the graph (None auto-scales to the data)
+pub struct GraphSpec<'a> {
+    pub id: GraphId,
+    pub label: &'static str,
+    pub series: &'a GraphSeries,
+    pub color: Color,
+    pub fmt_value: fn(f32) -> String,
+    pub max_scale: Option<f32>,
+}
+
+// lowest, mean and highest point, None for an empty series
+pub fn series_stats(points: &VecDeque<f32>) -> Option<(f32, f32, f32)> {
+    if points.is_empty() {
+        return None;
+    }
+    let min = points.iter().cloned().fold(f32::INFINITY, f32::min);
+    let max = points.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let avg = points.iter().sum::<f32>() / points.len() as f32;
+    Some((min, avg, max))
+}
+
+// changes smaller than this share of the larger point count as flat
+const TREND_DEADBAND: f32 = 0.02;
+
+// direction of the last step: ↑, ↓ or → when flat or too short to tell
+pub fn trend_arrow(points: &VecDeque<f32>) -> &'static str {
+    let mut newest = points.iter().rev();
+    let (Some(&last), Some(&prev)) = (newest.next(), newest.next()) else {
+        return "→";
+    };
+    let band = last.abs().max(prev.abs()) * TREND_DEADBAND;
+    if last - prev > band {
+        "↑"
+    } else if prev - last > band {
+        "↓"
+    } else {
+        "→"
+    }
+}
+
+// the line chart behind both the cards and the expanded view; axes labels
+// every grid line instead of only the auto-scaled top
+struct Plot<'a> {
+    data: &'a VecDeque<f32>,
+    color: Color,
+    fmt_value: fn(f32) -> String,
+    max_scale: Option<f32>,
+    axes: bool,
+}
+
+// this implements the canvas drawing
+impl<'a> canvas::Program<Message> for Plot<'a> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &iced::Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        cursor: iced::mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        // this creates a drawing frame with w and h
+        let mut frame = Frame::new(renderer, bounds.size());
+        let w = bounds.width;
+        let h = bounds.height;
+        let data = self.data;
+        let ink = if is_dark(theme) { Color::WHITE } else { Color::BLACK };
+        // finds max, unless the scale is fixed
+        let max = self.max_scale.unwrap_or_else(|| data.iter().cloned().fold(1.0, f32::max));
+
+        // faint quarter lines and a slightly stronger baseline
+        for (frac, alpha) in [(0.0, 0.35), (0.25, 0.12), (0.5, 0.12), (0.75, 0.12), (1.0, 0.12)] {
+            let y = (h - 2.0) - frac * (h - 4.0);
+            let line = canvas::Path::line([0.0, y].into(), [w, y].into());
+            frame.stroke(&line, Stroke::default().with_width(1.0).with_color(Color { a: alpha, ..ink }));
+            if self.axes && frac > 0.0 {
+                frame.fill_text(canvas::Text {
+                    content: (self.fmt_value)(max * frac),
+                    position: [2.0, y + 2.0].into(),
+                    color: Color { a: 0.6, ..ink },
+                    size: 12.0.into(),
+                    ..canvas::Text::default()
+                });
+            }
+        }
+
+        // auto-scaled graphs say what the top line means
+        if !self.axes && self.max_scale.is_none() && !data.is_empty() {
+            frame.fill_text(canvas::Text {
+                content: (self.fmt_value)(max),
+                position: [2.0, 2.0].into(),
+                color: Color { a: 0.5, ..ink },
+                size: 11.0.into(),
+                ..canvas::Text::default()
+            });
+        }
+
+        if data.len() >= 2 {
+            let step = w / (data.len().saturating_sub(1) as f32);
+            let mut builder = iced_widget::canvas::path::Builder::new();
+            // same line, closed down to the baseline for the fill
+            let mut area = iced_widget::canvas::path::Builder::new();
+            area.move_to([0.0, h].into());
+
+            for (i, v) in data.iter().enumerate() {
+                let x = i as f32 * step;
+                let margin = 2.0;
+                let y = (h - margin) - (v / max).min(1.0) * (h - 2.0 - margin);
+                if i == 0 {
+                    builder.move_to([x, y].into());
+                } else {
+                    builder.line_to([x, y].into());
+                }
+                area.line_to([x, y].into());
+            }
+            area.line_to([(data.len() - 1) as f32 * step, h].into());
+            area.close();
+
+            let path = builder.build();
+            
+            let stroke = Stroke::default()
+                .with_width(2.0)
+                .with_color(self.color);
+            
+            frame.fill(&area.build(), Color { a: 0.25, ..self.color });
+            frame.stroke(&path, stroke);
+
+            // hover readout: guide line plus the value under the cursor
+            if let Some(pos) = cursor.position_in(bounds) {
+                let i = ((pos.x / step).round() as usize).min(data.len() - 1);
+                let x = i as f32 * step;
+                let guide_color = Color { a: 0.5, ..ink };
+                let guide = canvas::Path::line([x, 0.0].into(), [x, h].into());
+                frame.stroke(&guide, Stroke::default().with_width(1.0).with_color(guide_color));
+
+                // keep the label inside the card on the right half
+                let (anchor, label_x) = if x > w / 2.0 {
+                    (iced::alignment::Horizontal::Right, x - 4.0)
+                } else {
+                    (iced::alignment::Horizontal::Left, x + 4.0)
+                };
+                frame.fill_text(canvas::Text {
+                    content: (self.fmt_value)(data[i]),
+                    position: [label_x, 2.0].into(),
+                    color: ink,
+                    size: 12.0.into(),
+                    horizontal_alignment: anchor,
+                    ..canvas::Text::default()
+                });
+            }
+        }
+        vec![frame.into_geometry()]
+    }
+}
 
-    // this implements the canvas drawing
-    impl<'a> canvas::Program<Message> for Plot<'a> {
+// creates a label graph widget, fmt_value formats the latest point
+pub fn sparkline<'a>(spec: &GraphSpec<'a>) -> Element<'a, Message> {
+    let canvas = iced_widget::canvas(Plot {
+        data: &spec.series.points,
+        color: spec.color,
+        fmt_value: spec.fmt_value,
+        max_scale: spec.max_scale,
+        axes: false,
+    })
+    .width(Length::Fill)
+    .height(80.0);
+
+    let current = spec
+        .series
+        .points
+        .back()
+        .map_or("—".to_string(), |v| (spec.fmt_value)(*v));
+
+    let header = row![
+        text(spec.label).size(14),
+        Space::with_width(Length::Fill),
+        tinted(text(current).size(14), spec.color),
+    ]
+    .align_items(Alignment::Center);
+
+    column![header, canvas]
+        .spacing(4)
+        .width(Length::FillPortion(1))
+        .into()
+}
+
+// bare line for a table cell: fixed 0-100 scale, no grid or hover
+pub fn mini_sparkline<'a>(series: &'a GraphSeries, color: Color) -> Element<'a, Message> {
+    struct Mini<'a>(&'a VecDeque<f32>, Color);
+
+    impl<'a> canvas::Program<Message> for Mini<'a> {
         type State = ();
 
         fn draw(
@@ -21,66 +210,138 @@ pub fn sparkline<'a>(label: &str, series: &'a GraphSeries, color: Color) -> Elem
             bounds: Rectangle,
             _cursor: iced::mouse::Cursor,
         ) -> Vec<canvas::Geometry> {
-            // this creates a drawing frame with w and h
             let mut frame = Frame::new(renderer, bounds.size());
-            let w = bounds.width;
-            let h = bounds.height;
             let data = self.0;
-
             if data.len() >= 2 {
-                // finds max
-                let max = data.iter().cloned().fold(1.0, f32::max);
-                let step = w / (data.len().saturating_sub(1) as f32);
-                let mut builder = iced_widget::canvas::path::Builder::new();
-
+                let (w, h) = (bounds.width, bounds.height);
+                // always spread over the full history length so a young
+                // process starts at the left instead of being stretched
+                let step = w / (PROC_GRAPH_POINTS - 1) as f32;
+                let x0 = w - (data.len() - 1) as f32 * step;
+                let mut builder = canvas::path::Builder::new();
                 for (i, v) in data.iter().enumerate() {
-                    let x = i as f32 * step;
-                    let margin = 2.0;
-                    let y = (h - margin) - (v / max) * (h - 2.0 - margin);
+                    let x = x0 + i as f32 * step;
+                    let y = (h - 1.0) - (v / 100.0).clamp(0.0, 1.0) * (h - 2.0);
                     if i == 0 {
                         builder.move_to([x, y].into());
                     } else {
                         builder.line_to([x, y].into());
                     }
                 }
-
-                let path = builder.build();
-                
-                let stroke = Stroke::default()
-                    .with_width(2.0)
-                    .with_color(self.1);
-                
-                frame.stroke(&path, stroke);
+                frame.stroke(&builder.build(), Stroke::default().with_width(1.0).with_color(self.1));
             }
             vec![frame.into_geometry()]
         }
     }
 
-    let canvas = iced_widget::canvas(Plot(&series.points, color))
+    iced_widget::canvas(Mini(&series.points, color))
         .width(Length::Fill)
-        .height(80.0);
-
-    column![text(label).size(14), canvas]
-        .spacing(4)
-        .width(Length::FillPortion(1))
+        .height(16.0)
         .into()
 }
 
-pub fn graph_card<'a>(label: &str, series: &'a GraphSeries, color: Color) -> Element<'a, Message> {
-    let sparkline_widget = sparkline(label, series, color);
+// rounded grey background shared by the graph cards
+fn card_appearance(theme: &Theme) -> container::Appearance {
+    let bg = if is_dark(theme) {
+        Color::from_rgb(0.25, 0.25, 0.25)
+    } else {
+        Color::from_rgb(0.9, 0.9, 0.92)
+    };
+    container::Appearance {
+        background: Some(iced::Background::Color(bg)),
+        border: iced::Border {
+            radius: 8.0.into(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+// same card with an icy outline while graph updates are paused
+fn frozen_card_appearance(theme: &Theme) -> container::Appearance {
+    container::Appearance {
+        border: iced::Border {
+            color: Color::from_rgba(0.55, 0.8, 1.0, 0.6),
+            width: 1.0,
+            radius: 8.0.into(),
+        },
+        ..card_appearance(theme)
+    }
+}
+
+// clicking a card opens it in the expanded view
+pub fn graph_card<'a>(spec: GraphSpec<'a>, frozen: bool) -> Element<'a, Message> {
+    let sparkline_widget = sparkline(&spec);
     
-    container(sparkline_widget)
+    let card = container(sparkline_widget)
         .padding(12)
+        .width(Length::Fill)
+        .style(if frozen { frozen_card_appearance } else { card_appearance });
+    container(mouse_area(card).on_press(Message::ExpandGraph(spec.id)))
         .width(Length::FillPortion(1)) // multiple cards share space equally
-        .style(|_theme: &Theme| {
-            container::Appearance {
-                background: Some(iced::Background::Color(Color::from_rgb(0.25, 0.25, 0.25))),
-                border: iced::Border {
-                    radius: 8.0.into(),
-                    ..Default::default()
-                },
-                ..Default::default()
-            }
-        })
         .into()
-}
\ No newline at end of file
+}
+
+// one metric at full size, with labelled axes and window statistics;
+// sample_secs is the time between points
+pub fn expanded_graph<'a>(spec: GraphSpec<'a>, sample_secs: f32) -> Element<'a, Message> {
+    let fmt = spec.fmt_value;
+    let points = &spec.series.points;
+    let stats = match series_stats(points) {
+        Some((min, avg, max)) => format!("min {}   avg {}   max {}", fmt(min), fmt(avg), fmt(max)),
+        None => "no data yet".to_string(),
+    };
+    let span = points.len().saturating_sub(1) as f32 * sample_secs;
+    let dim = Color::from_rgb(0.6, 0.6, 0.6);
+
+    let header = row![
+        text(spec.label).size(18),
+        Space::with_width(12),
+        tinted(text(stats).size(14), spec.color),
+        Space::with_width(Length::Fill),
+        button(text("×").size(18).shaping(text::Shaping::Advanced))
+            .on_press(Message::CloseGraph)
+            .style(iced::theme::Button::Text)
+            .padding([0, 8]),
+    ]
+    .align_items(Alignment::Center);
+    let plot = iced_widget::canvas(Plot {
+        data: points,
+        color: spec.color,
+        fmt_value: fmt,
+        max_scale: spec.max_scale,
+        axes: true,
+    })
+    .width(Length::Fill)
+    .height(300.0);
+    let time_axis = row![
+        tinted(text(format!("{} ago", fmt_duration(span as u64))).size(12), dim),
+        Space::with_width(Length::Fill),
+        tinted(text("now").size(12), dim),
+    ];
+
+    container(column![header, plot, time_axis].spacing(6))
+        .padding(16)
+        .width(900.0)
+        .style(card_appearance)
+        .into()
+}
+
+// placeholder card for a metric the system doesn't expose
+pub fn unavailable_card<'a>(label: &str) -> Element<'a, Message> {
+    let body = column![
+        text(label.to_string()).size(14),
+        tinted(text("N/A").size(20), Color::from_rgb(0.6, 0.6, 0.6))
+            .width(Length::Fill)
+            .height(80.0)
+            .center_x()
+            .center_y(),
+    ]
+    .spacing(4);
+
+    container(body)
+        .padding(12)
+        .width(Length::FillPortion(1))
+        .style(card_appearance)
+        .into()
+}
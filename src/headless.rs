@@ -0,0 +1,116 @@
+// This file prints one snapshot to stdout for `--headless`, no window needed
+
+use std::collections::HashMap;
+use std::io::{self, BufWriter, Write};
+
+use crate::app::{filter_regex, filter_sort_rows};
+use crate::export::{write_json, JsonSnapshot};
+use crate::models::{ColumnId, DiskUsage, ProcRow, SettingsModel, Suggestion, SystemStats};
+use crate::suggestions::make_suggestions;
+use crate::system_monitor::{ExtraReads, Monitor};
+use crate::util::{fmt_bytes, fmt_duration, fmt_utc};
+use crate::view::cell_text;
+
+// samples twice so cpu and I/O rates cover a real interval, then prints the
+// table the GUI would show with the saved filter, sort and columns
+pub fn run(settings: &SettingsModel, json: bool) -> io::Result<()> {
+    let mut monitor = Monitor::new();
+    monitor.set_extra_reads(ExtraReads::for_settings(settings, None));
+    monitor.sample();
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL.max(std::time::Duration::from_millis(500)));
+    let snap = monitor.sample();
+    let (stats, rows) = (snap.stats, snap.rows);
+
+    let suggestions = make_suggestions(
+        &rows,
+        if settings.alerts_on_cpu { stats.cpu_percent } else { 0.0 },
+        if settings.alerts_on_mem { stats.mem_percent() } else { 0.0 },
+        &settings.thresholds,
+        // one sample can't show a trend
+        &HashMap::new(),
+        0.0,
+        &snap.disks,
+    );
+    let filter_re = filter_regex(settings);
+    let shown = filter_sort_rows(&rows, settings, filter_re.as_ref());
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    if json {
+        // a single sample has no graph history to report
+        write_json(&mut out, &JsonSnapshot::new(&stats, None, shown, &suggestions))?;
+    } else {
+        write_table(&mut out, &stats, &snap.disks, &shown, &settings.columns, &suggestions)?;
+    }
+    out.flush()
+}
+
+// wide enough for typical values; the name column is truncated to fit
+fn text_width(id: ColumnId) -> usize {
+    match id {
+        ColumnId::Name => 28,
+        ColumnId::User => 12,
+        ColumnId::Pid
+        | ColumnId::Priority
+        | ColumnId::Threads
+        | ColumnId::OpenFiles
+        | ColumnId::Cpu
+        | ColumnId::MemPercent => 7,
+        _ => 11,
+    }
+}
+
+fn write_table<W: Write>(
+    out: &mut W,
+    stats: &SystemStats,
+    disks: &[DiskUsage],
+    rows: &[&ProcRow],
+    columns: &[ColumnId],
+    suggestions: &[Suggestion],
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "CPU {:.1}%   Mem {} / {}   Swap {} / {}   {} processes",
+        stats.cpu_percent,
+        fmt_bytes(stats.used_mem),
+        fmt_bytes(stats.total_mem),
+        fmt_bytes(stats.used_swap),
+        fmt_bytes(stats.total_swap),
+        stats.states.total
+    )?;
+    if let Some([one, five, fifteen]) = stats.load_avg {
+        writeln!(out, "Load {:.2} {:.2} {:.2}", one, five, fifteen)?;
+    }
+    writeln!(out, "Up {}, booted {}", fmt_duration(stats.uptime_secs), fmt_utc(stats.boot_time))?;
+    for d in disks {
+        writeln!(
+            out,
+            "Disk {} {} / {} ({:.0}%)",
+            d.mount,
+            fmt_bytes(d.used()),
+            fmt_bytes(d.total),
+            d.used_percent()
+        )?;
+    }
+    for s in suggestions {
+        writeln!(out, "! {}: {}", s.title, s.detail)?;
+    }
+    writeln!(out)?;
+
+    let header: Vec<String> = columns
+        .iter()
+        .map(|&id| format!("{:<w$}", id.label(), w = text_width(id)))
+        .collect();
+    writeln!(out, "{}", header.join(" ").trim_end())?;
+    for p in rows {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|&id| {
+                let w = text_width(id);
+                let value: String = cell_text(id, p).chars().take(w).collect();
+                format!("{:<w$}", value)
+            })
+            .collect();
+        writeln!(out, "{}", cells.join(" ").trim_end())?;
+    }
+    Ok(())
+}
@@ -5,7 +5,19 @@ pub mod styles;
 pub mod graphs;
 pub mod suggestions;
 pub mod system_monitor;
+pub mod gpu;
 pub mod view;
+pub mod context_menu;
+pub mod export;
+pub mod headless;
+pub mod config;
+pub mod alert_log;
+pub mod notify;
+pub mod tray;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(all(feature = "procnet", target_os = "linux"))]
+pub mod procnet;
 pub mod platform;
 pub mod util;
 
@@ -3,8 +3,20 @@ pub mod styles;
 pub mod graphs;
 pub mod suggestions;
 pub mod system_monitor;
+pub mod gpu;
 pub mod view;
+pub mod context_menu;
 pub mod app;
+mod export;
+mod headless;
+mod config;
+mod alert_log;
+mod notify;
+mod tray;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(all(feature = "procnet", target_os = "linux"))]
+mod procnet;
 mod platform;
 mod util;
 
@@ -12,9 +24,37 @@ use app::ProcMonApp;
 use iced::Application;
 
 fn main() -> iced::Result {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let has = |flag: &str| args.iter().any(|a| a == flag);
+    // print one snapshot and exit, for servers without a display
+    if has("--headless") || has("--once") {
+        match headless::run(&config::load_settings(), has("--json")) {
+            // piping into `head` closes stdout early, that's not a failure
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => {
+                eprintln!("headless snapshot failed: {}", e);
+                std::process::exit(1);
+            }
+            _ => return Ok(()),
+        }
+    }
+
     // customized for unix display scale
     #[cfg(target_family = "unix")]
     std::env::set_var("WINIT_X11_SCALE_FACTOR", "1.27");
 
-    ProcMonApp::run(iced::Settings::default())
+    let settings = config::load_settings();
+    let mut window = iced::window::Settings {
+        // closing is handled by the app so it can save settings first
+        exit_on_close_request: false,
+        ..Default::default()
+    };
+    if let Some(geometry) = settings.window {
+        window.size = iced::Size::new(geometry.width, geometry.height);
+        window.position = geometry.startup_position();
+    }
+
+    ProcMonApp::run(iced::Settings {
+        window,
+        ..iced::Settings::with_flags(settings)
+    })
 }
@@ -0,0 +1,136 @@
+// This file serves a Prometheus /metrics endpoint (cargo feature "metrics")
+
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+use crate::models::{InterfaceRate, ProcRow};
+
+// how many processes get their own series
+pub const TOP_N: usize = 10;
+
+#[derive(Debug, Clone, Default)]
+pub struct ProcSample {
+    pub pid: i32,
+    pub name: String,
+    pub cpu: f32,
+    pub mem_bytes: u64,
+}
+
+impl From<&ProcRow> for ProcSample {
+    fn from(p: &ProcRow) -> Self {
+        ProcSample { pid: p.pid, name: p.name.clone(), cpu: p.cpu, mem_bytes: p.mem_bytes }
+    }
+}
+
+// what refresh_now last saw, read by the server thread
+#[derive(Debug, Clone, Default)]
+pub struct MetricsSnapshot {
+    pub cpu_percent: f32,
+    pub mem_percent: f32,
+    pub swap_percent: f32,
+    pub net: Vec<InterfaceRate>,
+    pub top_cpu: Vec<ProcSample>,
+    pub top_mem: Vec<ProcSample>,
+}
+
+pub type SharedSnapshot = Arc<Mutex<MetricsSnapshot>>;
+
+// highest N rows by cpu and by memory
+pub fn top_processes(rows: &[ProcRow]) -> (Vec<ProcSample>, Vec<ProcSample>) {
+    let mut by_cpu: Vec<&ProcRow> = rows.iter().collect();
+    by_cpu.sort_by(|a, b| b.cpu.partial_cmp(&a.cpu).unwrap_or(std::cmp::Ordering::Equal));
+    let mut by_mem: Vec<&ProcRow> = rows.iter().collect();
+    by_mem.sort_by_key(|p| std::cmp::Reverse(p.mem_bytes));
+    (
+        by_cpu.into_iter().take(TOP_N).map(ProcSample::from).collect(),
+        by_mem.into_iter().take(TOP_N).map(ProcSample::from).collect(),
+    )
+}
+
+// label values need backslash, quote and newline escaped
+fn escape_label(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn header(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+}
+
+// prometheus text exposition format
+pub fn render(snap: &MetricsSnapshot) -> String {
+    let mut out = String::new();
+
+    header(&mut out, "procdeck_cpu_percent", "Total CPU usage in percent.");
+    let _ = writeln!(out, "procdeck_cpu_percent {}", snap.cpu_percent);
+    header(&mut out, "procdeck_memory_percent", "Used memory in percent.");
+    let _ = writeln!(out, "procdeck_memory_percent {}", snap.mem_percent);
+    header(&mut out, "procdeck_swap_percent", "Used swap in percent.");
+    let _ = writeln!(out, "procdeck_swap_percent {}", snap.swap_percent);
+
+    header(&mut out, "procdeck_net_receive_bytes_per_second", "Receive rate per interface.");
+    for r in &snap.net {
+        let _ = writeln!(
+            out,
+            "procdeck_net_receive_bytes_per_second{{interface=\"{}\"}} {}",
+            escape_label(&r.name),
+            r.rx_bps
+        );
+    }
+    header(&mut out, "procdeck_net_transmit_bytes_per_second", "Transmit rate per interface.");
+    for r in &snap.net {
+        let _ = writeln!(
+            out,
+            "procdeck_net_transmit_bytes_per_second{{interface=\"{}\"}} {}",
+            escape_label(&r.name),
+            r.tx_bps
+        );
+    }
+
+    header(&mut out, "procdeck_process_cpu_percent", "CPU usage of the busiest processes.");
+    for p in &snap.top_cpu {
+        let _ = writeln!(
+            out,
+            "procdeck_process_cpu_percent{{pid=\"{}\",name=\"{}\"}} {}",
+            p.pid,
+            escape_label(&p.name),
+            p.cpu
+        );
+    }
+    header(&mut out, "procdeck_process_memory_bytes", "Memory of the largest processes.");
+    for p in &snap.top_mem {
+        let _ = writeln!(
+            out,
+            "procdeck_process_memory_bytes{{pid=\"{}\",name=\"{}\"}} {}",
+            p.pid,
+            escape_label(&p.name),
+            p.mem_bytes
+        );
+    }
+    out
+}
+
+// binds the address and answers requests on a background thread
+pub fn serve(addr: &str) -> std::io::Result<SharedSnapshot> {
+    let server = tiny_http::Server::http(addr).map_err(std::io::Error::other)?;
+    let shared = SharedSnapshot::default();
+    let snap = shared.clone();
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let response = if request.url() == "/metrics" {
+                // clone and release the lock before formatting
+                let current = snap.lock().map(|s| s.clone()).unwrap_or_default();
+                let header = tiny_http::Header::from_bytes(
+                    &b"Content-Type"[..],
+                    &b"text/plain; version=0.0.4"[..],
+                )
+                .expect("static header is valid");
+                tiny_http::Response::from_string(render(&current)).with_header(header)
+            } else {
+                tiny_http::Response::from_string("not found").with_status_code(404)
+            };
+            let _ = request.respond(response);
+        }
+    });
+    Ok(shared)
+}
@@ -1,78 +1,686 @@
 // This file defines data structure and types
 
 use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 
-// how many data points to display in graphs
+// interface picker entry meaning every interface
+pub const ALL_INTERFACES: &str = "All";
+
+// how many data points to display in graphs, one per GRAPH_INTERVAL so the
+// window is always two minutes
 pub const GRAPH_POINTS: usize = 120;
+pub const GRAPH_INTERVAL: Duration = Duration::from_secs(1);
+// how long a freshly spawned process stays marked in the table
+pub const NEW_PROCESS_HIGHLIGHT: Duration = Duration::from_secs(5);
+// a gap longer than this many intervals (paused, frozen graphs) isn't
+// filled in, the graph just carries on from now
+const MAX_GRAPH_FILL: u32 = 3;
+// per-process history is kept short since there's one per pid
+pub const PROC_GRAPH_POINTS: usize = 30;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// how many past filters the recent dropdown keeps
+pub const RECENT_FILTERS: usize = 10;
+
+// valid range for unix nice values
+pub const NICE_MIN: i32 = -20;
+pub const NICE_MAX: i32 = 19;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 pub enum SortKey {
     Pid,
     Name,
+    User,
+    Priority,
+    #[default]
     Cpu,
     Mem,
+    Swap,
     Read,
     Write,
+    Threads,
+    OpenFiles,
+    ReadTotal,
+    WriteTotal,
+    Uptime,
+    NetRx,
+    NetTx,
 }
 
-impl Default for SortKey {
-    fn default() -> Self {
-        SortKey::Cpu
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 pub enum SortDir {
     Asc,
+    #[default]
     Desc,
 }
 
-impl Default for SortDir {
-    fn default() -> Self {
-        SortDir::Desc
+// table columns the user can show, hide and reorder
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ColumnId {
+    Pid,
+    Name,
+    User,
+    Priority,
+    Cpu,
+    Mem,
+    MemPercent,
+    Swap,
+    Read,
+    Write,
+    Threads,
+    OpenFiles,
+    ReadTotal,
+    WriteTotal,
+    Uptime,
+    NetRx,
+    NetTx,
+}
+
+impl ColumnId {
+    pub const ALL: [ColumnId; 17] = [
+        ColumnId::Pid,
+        ColumnId::Name,
+        ColumnId::User,
+        ColumnId::Priority,
+        ColumnId::Cpu,
+        ColumnId::Mem,
+        ColumnId::MemPercent,
+        ColumnId::Swap,
+        ColumnId::Read,
+        ColumnId::Write,
+        ColumnId::Threads,
+        ColumnId::OpenFiles,
+        ColumnId::ReadTotal,
+        ColumnId::WriteTotal,
+        ColumnId::Uptime,
+        ColumnId::NetRx,
+        ColumnId::NetTx,
+    ];
+
+    // memory share, swap, open files, lifetime I/O totals and the network
+    // estimate are opt in
+    pub fn defaults() -> Vec<ColumnId> {
+        ColumnId::ALL
+            .into_iter()
+            .filter(|c| {
+                !matches!(
+                    c,
+                    ColumnId::MemPercent
+                        | ColumnId::Swap
+                        | ColumnId::OpenFiles
+                        | ColumnId::ReadTotal
+                        | ColumnId::WriteTotal
+                        | ColumnId::NetRx
+                        | ColumnId::NetTx
+                )
+            })
+            .collect()
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColumnId::Pid => "PID",
+            ColumnId::Name => "Name",
+            ColumnId::User => "User",
+            ColumnId::Priority => "Nice",
+            ColumnId::Cpu => "CPU %",
+            ColumnId::Mem => "Memory",
+            ColumnId::MemPercent => "Mem %",
+            ColumnId::Swap => "Swap",
+            ColumnId::Read => "Read/s",
+            ColumnId::Write => "Write/s",
+            ColumnId::Threads => "Threads",
+            ColumnId::OpenFiles => "Files",
+            ColumnId::ReadTotal => "Read total",
+            ColumnId::WriteTotal => "Written",
+            ColumnId::Uptime => "Uptime",
+            ColumnId::NetRx => "Net RX/s",
+            ColumnId::NetTx => "Net TX/s",
+        }
+    }
+
+    pub fn sort_key(self) -> SortKey {
+        match self {
+            ColumnId::Pid => SortKey::Pid,
+            ColumnId::Name => SortKey::Name,
+            ColumnId::User => SortKey::User,
+            ColumnId::Priority => SortKey::Priority,
+            ColumnId::Cpu => SortKey::Cpu,
+            ColumnId::Mem => SortKey::Mem,
+            // same order as the bytes, no key of its own needed
+            ColumnId::MemPercent => SortKey::Mem,
+            ColumnId::Swap => SortKey::Swap,
+            ColumnId::Read => SortKey::Read,
+            ColumnId::Write => SortKey::Write,
+            ColumnId::Threads => SortKey::Threads,
+            ColumnId::OpenFiles => SortKey::OpenFiles,
+            ColumnId::ReadTotal => SortKey::ReadTotal,
+            ColumnId::WriteTotal => SortKey::WriteTotal,
+            ColumnId::Uptime => SortKey::Uptime,
+            ColumnId::NetRx => SortKey::NetRx,
+            ColumnId::NetTx => SortKey::NetTx,
+        }
     }
 }
 
+// a process started from the start box, kept so its exit can be reported
+#[derive(Debug)]
+pub struct Launched {
+    pub cmd: String,
+    pub child: std::process::Child,
+    pub exit: Option<std::process::ExitStatus>, // None while running
+}
+
+// one management action taken this session, for the history panel
+#[derive(Debug, Clone)]
+pub struct ActionRecord {
+    pub at: u64, // unix seconds
+    pub pid: i32,
+    pub name: String,
+    pub action: String,
+    pub result: Result<(), String>,
+}
+
+// history panel columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HistoryKey {
+    #[default]
+    Time,
+    Pid,
+    Name,
+    Action,
+    Result,
+}
+
+// what the start box launches; fields are the raw box contents
+#[derive(Debug, Clone, Default)]
+pub struct StartRequest {
+    pub cmd: String,
+    pub cwd: String,
+    pub env: String,
+    pub detached: bool,
+}
+
+impl StartRequest {
+    pub fn from_settings(s: &SettingsModel) -> Self {
+        StartRequest {
+            cmd: s.cmd_to_start.clone(),
+            cwd: s.start_cwd.clone(),
+            env: s.start_env.clone(),
+            detached: s.start_detached,
+        }
+    }
+}
+
+// "KEY=value; OTHER=value" into pairs; values may contain '=' and spaces
+pub fn parse_env(s: &str) -> Result<Vec<(String, String)>, String> {
+    s.split(';')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| match part.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.to_string())),
+            _ => Err(format!("expected KEY=value, got \"{}\"", part)),
+        })
+        .collect()
+}
+
 // application events
 #[derive(Debug, Clone)]
 pub enum Message {
     Tick,
     FilterChanged(String),
+    FilterSubmitted, // Enter in the filter box, remembers it
+    ApplyRecentFilter(String),
+    RegexFilterChanged(bool),
     SortBy(SortKey),
     Kill(i32),
+    KillTree(i32),
+    Terminate(i32),
     Suspend(i32),
     Resume(i32),
     Boost(i32),
     Lower(i32),
+    SetPriority(i32, i32),
+    #[cfg(target_family = "windows")]
+    SetPriorityClass(i32, PriorityClass), // realtime asks for confirmation first
+    #[cfg(target_family = "windows")]
+    ConfirmRealtime,
+    #[cfg(target_family = "windows")]
+    CancelRealtime,
+    SendSignal(i32, i32),
+    ReapZombies(i32), // SIGCHLD to the parent
+    Restart(i32),
+    RestartDone(i32, Result<(), String>),
+    #[cfg(target_os = "linux")]
+    EditAffinity(i32),
+    #[cfg(target_os = "linux")]
+    AffinityCore(usize, bool),
+    #[cfg(target_os = "linux")]
+    SetAffinity(i32, Vec<usize>),
+    #[cfg(target_os = "linux")]
+    CloseAffinity,
     StartChanged(String),
-    StartNow,
+    StartCwdChanged(String),
+    StartEnvChanged(String),
+    ToggleStartOptions,
+    StartDetachedChanged(bool),
+    ClearLaunched,
+    ToggleHistory,
+    HistoryFilterChanged(String),
+    SortHistory(HistoryKey),
+    StartNow(StartRequest),
     CpuAlertChanged(bool),
     MemAlertChanged(bool),
+    AlertLogChanged(bool),
+    NotifyChanged(bool),
+    NotifyCooldown(u64),
+    ExportCsv,
+    ExportJson,
+    DismissSuggestion(String),
+    SelectProcess(i32),
+    TogglePause,
+    ToggleGraphsPause,
+    WindowResized(u32, u32),
+    WindowMoved(i32, i32),
+    CloseRequested,
+    ToggleTheme,
+    NetInterfaceSelected(String),
+    ExcludeLoopbackChanged(bool),
+    NetBitsChanged(bool),
+    WatchdogEnabled(bool),
+    WatchdogMetric(WatchMetric),
+    WatchdogThreshold(u8),
+    WatchdogDuration(u64),
+    WatchdogAction(WatchAction),
+    MinimizeToTrayChanged(bool),
+    ToggleColumnMenu,
+    ColumnVisible(ColumnId, bool),
+    MoveColumn(ColumnId, i32), // -1 moves left, 1 moves right
+    FocusFilter,
+    EscapePressed,
+    KillSelected, // asks for confirmation first
+    ConfirmKill,
+    CancelKill,
+    KillAllMatching, // every row the filter shows, asks first
+    ConfirmKillAll,
+    CancelKillAll,
+    ConfirmCritical,
+    CancelCritical,
+    AllowCriticalChanged(bool),
+    GroupByNameChanged(bool),
+    MineOnlyChanged(bool),
+    CompactChanged(bool),
+    RowLimitChanged(RowLimit),
+    SmoothCpuChanged(bool),
+    CpuAlphaChanged(f32),
+    CheckProcess(i32, bool),
+    BatchKill,
+    BatchSuspend,
+    BatchResume,
+    ClearChecked,
+    MinCpuChanged(String),
+    MinMemChanged(String), // in MiB
+    ToggleGroup(String), // expand or collapse a name group
+    ShowContextMenu(i32, iced::Point), // point is relative to the row
+    ShowActionsMenu(i32), // the row's ⋯ button, drops the menu under it
+    CloseContextMenu,
+    ExpandGraph(GraphId),
+    CloseGraph,
+    CopyPid(i32),
+    CopyName(String),
+    CopyRow(i32), // labelled values, for pasting into a ticket
+    IdleHogMemChanged(u64), // in MiB
+    IdleHogCpuChanged(f32),
+    EnvFilterChanged(String),
+    ToggleEnvReveal,
+}
+
+// entry in the per-row signal menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignalChoice {
+    pub num: i32,
+    pub name: &'static str,
+}
+
+impl std::fmt::Display for SignalChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+// windows priority classes, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityClass {
+    Idle,
+    BelowNormal,
+    Normal,
+    AboveNormal,
+    High,
+    Realtime, // can starve input and drivers, always confirmed first
+}
+
+impl PriorityClass {
+    pub const ALL: [PriorityClass; 6] = [
+        PriorityClass::Idle,
+        PriorityClass::BelowNormal,
+        PriorityClass::Normal,
+        PriorityClass::AboveNormal,
+        PriorityClass::High,
+        PriorityClass::Realtime,
+    ];
+
+    // the nice value the Nice column shows for this class
+    pub fn nice(self) -> i32 {
+        match self {
+            PriorityClass::Idle => NICE_MAX,
+            PriorityClass::BelowNormal => 1,
+            PriorityClass::Normal => 0,
+            PriorityClass::AboveNormal => -1,
+            PriorityClass::High => -11,
+            PriorityClass::Realtime => NICE_MIN,
+        }
+    }
+
+    // closest class for a nice value, the reverse of nice()
+    pub fn from_nice(nice: i32) -> Self {
+        match nice {
+            n if n <= NICE_MIN => PriorityClass::Realtime,
+            n if n <= -11 => PriorityClass::High,
+            n if n < 0 => PriorityClass::AboveNormal,
+            0 => PriorityClass::Normal,
+            n if n < NICE_MAX => PriorityClass::BelowNormal,
+            _ => PriorityClass::Idle,
+        }
+    }
+}
+
+impl std::fmt::Display for PriorityClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PriorityClass::Idle => "Idle",
+            PriorityClass::BelowNormal => "Below normal",
+            PriorityClass::Normal => "Normal",
+            PriorityClass::AboveNormal => "Above normal",
+            PriorityClass::High => "High",
+            PriorityClass::Realtime => "Realtime",
+        })
+    }
 }
 
 // alert thresholds for notifications
-#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct Thresholds {
     pub cpu_percent: u8,
     pub mem_percent: u8,
+    pub idle_mem_bytes: u64, // idle processes above this are flagged as hogs
+    pub idle_cpu_percent: f32, // below this a process counts as idle
 }
 
-#[derive(Debug, Clone, Default)]
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds {
+            cpu_percent: 85,
+            mem_percent: 90,
+            idle_mem_bytes: 500 * 1024 * 1024,
+            idle_cpu_percent: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct Suggestion {
+    pub key: String, // stable id (category + PID) used for dismissal
     pub title: String,
     pub detail: String,
+    pub severity: Severity,
+    pub category: SuggestionCategory,
+    pub action: Option<SuggestionAction>, // one-click fix shown next to it
 }
 
-// application configuration
-#[derive(Debug, Clone, Default)]
+// ordered so that sorting descending puts critical first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+// what the suggestion is about, picks its colour
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum SuggestionCategory {
+    Cpu,
+    Memory,
+    Idle,
+    Files,
+    Stopped,
+    Zombie,
+    Disk,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum SuggestionAction {
+    // nudge a parent to reap its zombie children
+    SignalParent(i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum ThemeChoice {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl ThemeChoice {
+    pub fn theme(self) -> iced::Theme {
+        match self {
+            ThemeChoice::Dark => iced::Theme::Dark,
+            ThemeChoice::Light => iced::Theme::Light,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum WatchMetric {
+    #[default]
+    Cpu,
+    Mem,
+}
+
+impl std::fmt::Display for WatchMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            WatchMetric::Cpu => "CPU %",
+            WatchMetric::Mem => "Memory %",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum WatchAction {
+    #[default]
+    Suspend,
+    Kill,
+}
+
+impl std::fmt::Display for WatchAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            WatchAction::Suspend => "Suspend",
+            WatchAction::Kill => "Kill",
+        })
+    }
+}
+
+// how many rows the table keeps after sorting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum RowLimit {
+    #[default]
+    All,
+    Top(usize),
+}
+
+impl std::fmt::Display for RowLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RowLimit::All => f.write_str("All rows"),
+            RowLimit::Top(n) => write!(f, "Top {}", n),
+        }
+    }
+}
+
+// opt-in rule that acts on processes staying over a threshold
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct WatchdogSettings {
+    pub enabled: bool,
+    pub metric: WatchMetric,
+    pub threshold: u8, // percent of one core for CPU, of total RAM for memory
+    pub duration_secs: u64,
+    pub action: WatchAction,
+}
+
+impl Default for WatchdogSettings {
+    fn default() -> Self {
+        WatchdogSettings {
+            enabled: false,
+            metric: WatchMetric::Cpu,
+            threshold: 90,
+            duration_secs: 30,
+            action: WatchAction::Suspend,
+        }
+    }
+}
+
+// last known window size and position
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WindowGeometry {
+    pub width: f32,
+    pub height: f32,
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+}
+
+// largest coordinate we trust for a saved window position
+const MAX_SCREEN_EXTENT: i32 = 8192;
+
+impl WindowGeometry {
+    // iced can't tell us the monitor layout before the window exists, so a
+    // position that looks off-screen falls back to centering on the primary
+    // monitor instead of restoring it
+    pub fn startup_position(&self) -> iced::window::Position {
+        match (self.x, self.y) {
+            (Some(x), Some(y))
+                if x > -(self.width as i32) / 2
+                    && (0..MAX_SCREEN_EXTENT).contains(&y)
+                    && x < MAX_SCREEN_EXTENT =>
+            {
+                iced::window::Position::Specific(iced::Point::new(x as f32, y as f32))
+            }
+            _ => iced::window::Position::Centered,
+        }
+    }
+}
+
+// application configuration, persisted between runs
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct SettingsModel {
+    #[serde(skip)]
     pub filter: String,
+    pub recent_filters: Vec<String>, // most recent first, see RECENT_FILTERS
+    pub filter_regex: bool,
+    pub group_by_name: bool,
+    pub mine_only: bool,
+    pub compact: bool, // denser table rows
+    pub min_cpu: f32, // percent, 0 shows everything
+    pub min_mem: u64, // bytes, 0 shows everything
+    pub row_limit: RowLimit,
+    pub smooth_cpu: bool, // show an EMA of cpu instead of the raw reading
+    pub cpu_alpha: f32, // weight of the newest sample, 1.0 would be no smoothing
     pub sort_key: SortKey,
     pub sort_dir: SortDir,
+    #[serde(skip)]
     pub cmd_to_start: String,
+    #[serde(skip)]
+    pub start_cwd: String, // blank inherits ours
+    #[serde(skip)]
+    pub start_env: String, // "KEY=value; OTHER=value"
+    #[serde(skip)]
+    pub start_options_open: bool,
+    pub start_detached: bool, // launched processes outlive the monitor
     pub alerts_on_cpu: bool,
     pub alerts_on_mem: bool,
     pub thresholds: Thresholds,
+    pub theme: ThemeChoice,
+    pub net_interface: Option<String>, // None means all interfaces
+    pub exclude_loopback: bool,
+    pub net_bits: bool, // show network rates in bits/s
+    pub watchdog: WatchdogSettings,
+    pub minimize_to_tray: bool,
+    pub allow_critical_kill: bool, // skip the extra warning for system processes
+    pub columns: Vec<ColumnId>, // visible columns in display order
+    pub metrics_addr: String, // only used with the metrics feature
+    pub alert_log: bool,
+    pub alert_log_max_kb: u64, // rotate once the log reaches this size
+    pub notify: bool, // desktop notifications for cpu/mem alerts
+    pub notify_cooldown_secs: u64, // per metric
+    pub window: Option<WindowGeometry>,
+}
+
+impl SettingsModel {
+    // puts the current filter at the top of the recent list, once
+    pub fn remember_filter(&mut self) {
+        let filter = self.filter.trim();
+        if filter.is_empty() {
+            return;
+        }
+        let filter = filter.to_string();
+        self.recent_filters.retain(|f| *f != filter);
+        self.recent_filters.insert(0, filter);
+        self.recent_filters.truncate(RECENT_FILTERS);
+    }
+}
+
+impl Default for SettingsModel {
+    fn default() -> Self {
+        SettingsModel {
+            filter: String::new(),
+            recent_filters: Vec::new(),
+            filter_regex: false,
+            group_by_name: false,
+            mine_only: false,
+            compact: false,
+            min_cpu: 0.0,
+            min_mem: 0,
+            row_limit: RowLimit::All,
+            smooth_cpu: false,
+            cpu_alpha: 0.3,
+            sort_key: SortKey::Cpu,
+            sort_dir: SortDir::Desc,
+            cmd_to_start: String::new(),
+            start_cwd: String::new(),
+            start_env: String::new(),
+            start_options_open: false,
+            start_detached: false,
+            alerts_on_cpu: true,
+            alerts_on_mem: true,
+            thresholds: Thresholds::default(),
+            theme: ThemeChoice::Dark,
+            net_interface: None,
+            exclude_loopback: true,
+            net_bits: false,
+            watchdog: WatchdogSettings::default(),
+            minimize_to_tray: false,
+            allow_critical_kill: false,
+            columns: ColumnId::defaults(),
+            metrics_addr: "127.0.0.1:9184".to_string(),
+            alert_log: false,
+            alert_log_max_kb: 1024,
+            notify: false,
+            notify_cooldown_secs: 300,
+            window: None,
+        }
+    }
 }
 
 // time series for graph
@@ -83,38 +691,244 @@ pub struct GraphSeries {
 
 impl GraphSeries {
     pub fn push(&mut self, v: f32) {
-        if self.points.len() >= GRAPH_POINTS {
+        self.push_capped(v, GRAPH_POINTS);
+    }
+
+    pub fn push_capped(&mut self, v: f32, cap: usize) {
+        while self.points.len() >= cap {
             self.points.pop_front(); // remove oldest point
         }
         self.points.push_back(v); // add newest point
     }
 }
 
+// which system graph the expanded view shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphId {
+    Cpu,
+    Mem,
+    Swap,
+    DiskRead,
+    DiskWrite,
+    NetRx,
+    NetTx,
+    Temp,
+    Gpu,
+    Vram,
+    Load,
+}
+
+// hands out graph points on a wall-clock cadence, whatever the refresh rate
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GraphClock {
+    next: Option<Instant>,
+}
+
+impl GraphClock {
+    // points owed at `now`: none while early, several when a refresh ran late
+    // so the gap gets the current reading
+    pub fn due(&mut self, now: Instant) -> u32 {
+        match self.next {
+            Some(next) if now < next => 0,
+            Some(next) => {
+                let owed = ((now - next).as_secs_f32() / GRAPH_INTERVAL.as_secs_f32()) as u32 + 1;
+                if owed <= MAX_GRAPH_FILL {
+                    self.next = Some(next + GRAPH_INTERVAL * owed);
+                    return owed;
+                }
+                self.next = Some(now + GRAPH_INTERVAL);
+                1
+            }
+            None => {
+                self.next = Some(now + GRAPH_INTERVAL);
+                1
+            }
+        }
+    }
+}
+
 // graphs
 #[derive(Debug, Clone, Default)]
 pub struct SystemGraphs {
     pub cpu: GraphSeries,
     pub mem: GraphSeries,
+    pub swap: GraphSeries,
     pub disk_read: GraphSeries,
     pub disk_write: GraphSeries,
     pub net_rx: GraphSeries,
     pub net_tx: GraphSeries,
+    pub temp: GraphSeries,
+    pub gpu: GraphSeries,
+    pub vram: GraphSeries,
+    pub load: GraphSeries, // 1-minute load average
+}
+
+// coarse process state, folded down from sysinfo's ProcessStatus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+pub enum ProcState {
+    Running,
+    #[default]
+    Sleeping,
+    Stopped,
+    Zombie,
+    Other,
+}
+
+impl ProcState {
+    // stopped and zombie processes can't be using cpu right now, whatever
+    // their last reading says
+    pub fn can_run(self) -> bool {
+        !matches!(self, ProcState::Stopped | ProcState::Zombie)
+    }
+}
+
+// how many processes are in each state
+#[derive(Debug, Clone, Copy, Default, serde::Serialize)]
+pub struct StateCounts {
+    pub total: usize,
+    pub running: usize,
+    pub sleeping: usize,
+    pub stopped: usize,
+    pub zombie: usize,
+}
+
+impl StateCounts {
+    pub fn add(&mut self, state: ProcState) {
+        self.total += 1;
+        match state {
+            ProcState::Running => self.running += 1,
+            ProcState::Sleeping => self.sleeping += 1,
+            ProcState::Stopped => self.stopped += 1,
+            ProcState::Zombie => self.zombie += 1,
+            ProcState::Other => {}
+        }
+    }
+}
+
+// system wide totals for the top bar
+#[derive(Debug, Clone, Copy, Default, serde::Serialize)]
+pub struct SystemStats {
+    pub cpu_percent: f32,
+    pub used_mem: u64,
+    pub total_mem: u64,
+    pub used_swap: u64,
+    pub total_swap: u64,
+    pub states: StateCounts,
+    pub load_avg: Option<[f32; 3]>, // 1, 5 and 15 minutes, unix only
+    pub uptime_secs: u64,
+    pub boot_time: u64, // unix seconds
+}
+
+impl SystemStats {
+    pub fn mem_percent(&self) -> f32 {
+        self.used_mem as f32 * 100.0 / self.total_mem.max(1) as f32
+    }
+
+    // no swap configured means 0%, not a division by zero
+    pub fn swap_percent(&self) -> f32 {
+        if self.total_swap == 0 {
+            0.0
+        } else {
+            self.used_swap as f32 * 100.0 / self.total_swap as f32
+        }
+    }
 }
 
 // process row
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, serde::Serialize)]
 pub struct ProcRow {
     pub pid: i32,
     pub name: String,
+    pub user: String,
+    pub priority: Option<i32>,
     pub cpu: f32,
     pub mem_bytes: u64,
-    pub read_bps: u64,
-    pub write_bps: u64,
+    pub mem_percent: f32, // of total RAM
+    pub swap_bytes: Option<u64>, // swapped out, Linux only
+    pub read_bps: Option<u64>, // None when the process's I/O can't be seen
+    pub write_bps: Option<u64>,
+    pub read_total: Option<u64>, // bytes since the process started, None when hidden
+    pub write_total: Option<u64>,
+    pub status: ProcState,
+    pub start_time: u64, // unix seconds
+    pub threads: usize, // 0 where the platform doesn't report it
+    pub open_files: Option<usize>, // Linux only, None when the fd table can't be read
+    pub uptime_secs: u64,
+    pub mine: bool, // owned by the user running procdeck
+    pub parent: Option<i32>,
+    pub parent_name: Option<String>, // None when there's no parent or it has exited
+    pub restartable: bool, // has a command line to relaunch with, kernel threads don't
+    pub stopped_secs: u64, // how long it has been stopped, 0 unless status is Stopped
+    // estimated share of network traffic, None where it can't be computed
+    // (other platforms, the procnet feature off, or access denied)
+    pub net_rx_bps: Option<u64>,
+    pub net_tx_bps: Option<u64>,
+}
+
+// processes sharing a name, summary holds their summed usage
+#[derive(Debug, Clone)]
+pub struct ProcGroup<'a> {
+    pub summary: ProcRow,
+    pub members: Vec<&'a ProcRow>,
+}
+
+// extra info for the selected process
+#[derive(Debug, Clone, Default)]
+pub struct ProcDetails {
+    pub pid: i32,
+    pub name: String,
+    pub cmd: String,
+    pub exe: String,
+    pub run_time_secs: u64,
+    pub parent: Option<i32>,
+    pub parent_name: Option<String>,
+    pub threads: Option<usize>,
+    pub environ: Vec<(String, String)>, // empty when it can't be read
+}
+
+// key fragments that usually mean the value is a credential
+pub const SENSITIVE_ENV_MARKERS: [&str; 4] = ["TOKEN", "SECRET", "PASSWORD", "KEY"];
+
+pub fn is_sensitive_env(key: &str) -> bool {
+    let key = key.to_uppercase();
+    SENSITIVE_ENV_MARKERS.iter().any(|m| key.contains(m))
+}
+
+// space on one mounted filesystem
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct DiskUsage {
+    pub mount: String,
+    pub total: u64, // bytes
+    pub available: u64,
 }
 
+impl DiskUsage {
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.available)
+    }
+
+    pub fn used_percent(&self) -> f32 {
+        self.used() as f32 * 100.0 / self.total.max(1) as f32
+    }
+}
+
+// traffic of one network interface since the last refresh
+#[derive(Debug, Clone, Default)]
+pub struct InterfaceRate {
+    pub name: String,
+    pub rx_bps: f32,
+    pub tx_bps: f32,
+}
+
+// pid plus start time, since pids get reused
+pub type IoKey = (i32, u64);
+
 // for calculating I/O rates
 #[derive(Debug, Default, Clone, Copy)]
 pub struct IoSnapshot {
     pub read: u64,
     pub write: u64,
+    pub read_bps: u64, // rates from this reading, carried over a counter reset
+    pub write_bps: u64,
+    pub visible: bool, // whether the counters can be read at all, see platform::io_visible
 }
\ No newline at end of file
@@ -0,0 +1,16 @@
+// This file shows desktop notifications for threshold breaches
+
+// sent from a short lived thread since talking to the notification daemon
+// can block for a moment
+pub fn send(summary: String, body: String) {
+    std::thread::spawn(move || {
+        let result = notify_rust::Notification::new()
+            .appname("ProcDeck")
+            .summary(&summary)
+            .body(&body)
+            .show();
+        if let Err(e) = result {
+            eprintln!("failed to show notification: {}", e);
+        }
+    });
+}
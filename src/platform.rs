@@ -1,5 +1,16 @@
 #[cfg(target_family = "unix")]
-use std::process::Command;
+use std::process::{Command, Stdio};
+#[cfg(target_family = "unix")]
+use crate::models::SignalChoice;
+#[cfg(target_family = "windows")]
+use crate::models::PriorityClass;
+
+use std::path::Path;
+use std::process::Child;
+use std::time::{Duration, Instant};
+
+use crate::models::{NICE_MAX, NICE_MIN};
+use crate::system_monitor::process_tree;
 
 #[cfg(target_family = "windows")]
 use windows_sys::Win32::{
@@ -11,6 +22,7 @@ use windows_sys::Win32::{
         Threading::{
             OpenThread, SuspendThread, ResumeThread, SetPriorityClass, PROCESS_SET_INFORMATION,
             THREAD_SUSPEND_RESUME, BELOW_NORMAL_PRIORITY_CLASS, ABOVE_NORMAL_PRIORITY_CLASS,
+            IDLE_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS, HIGH_PRIORITY_CLASS,
         },
     },
 };
@@ -51,7 +63,233 @@ pub fn kill(pid: i32) -> std::io::Result<()> {
         nix::unistd::Pid::from_raw(pid),
         nix::sys::signal::Signal::SIGKILL,
     )
-    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+    .map_err(std::io::Error::from)
+}
+
+// polite SIGTERM so the process can clean up
+#[cfg(target_family = "unix")]
+pub fn terminate(pid: i32) -> std::io::Result<()> {
+    send_signal(pid, nix::sys::signal::Signal::SIGTERM)
+}
+
+// kills pid and all of its descendants, deepest first. The whole tree is
+// collected up front so children reparented mid-walk are still killed.
+// Critical descendants are left alone and listed in the error; pid itself
+// has already been through the confirmation.
+pub fn kill_tree(pid: i32) -> std::io::Result<()> {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes_specifics(sysinfo::ProcessRefreshKind::new());
+
+    let mut first_err = None;
+    let mut skipped = Vec::new();
+    for target in process_tree(&sys, pid) {
+        let name = sys.process(sysinfo::Pid::from_u32(target as u32)).map_or("", |p| p.name());
+        if target != pid && is_critical(target, name) {
+            skipped.push(target.to_string());
+            continue;
+        }
+        if let Err(e) = kill(target) {
+            first_err.get_or_insert(e);
+        }
+    }
+    if skipped.is_empty() {
+        return first_err.map_or(Ok(()), Err);
+    }
+    let mut msg = format!("skipped critical PIDs {}, kill them on their own", skipped.join(", "));
+    if let Some(e) = first_err {
+        msg = format!("{}; {}", explain_error(&e), msg);
+    }
+    Err(std::io::Error::other(msg))
+}
+
+// processes whose death takes the session or the whole system down with them
+#[cfg(target_os = "linux")]
+const CRITICAL_NAMES: &[&str] = &[
+    "systemd", "init", "Xorg", "Xwayland", "gnome-shell", "kwin_x11", "kwin_wayland",
+    "dbus-daemon", "systemd-logind", "gdm", "sddm", "lightdm", "sshd",
+];
+#[cfg(target_os = "macos")]
+const CRITICAL_NAMES: &[&str] = &["launchd", "kernel_task", "WindowServer", "loginwindow", "sshd"];
+#[cfg(all(target_family = "unix", not(any(target_os = "linux", target_os = "macos"))))]
+const CRITICAL_NAMES: &[&str] = &["init", "Xorg", "sshd"];
+#[cfg(target_family = "windows")]
+const CRITICAL_NAMES: &[&str] = &[
+    "System", "smss.exe", "csrss.exe", "wininit.exe", "winlogon.exe", "services.exe",
+    "lsass.exe", "dwm.exe", "explorer.exe",
+];
+
+// pid 0/1 (4 is System on Windows) or a name from the list above
+pub fn is_critical(pid: i32, name: &str) -> bool {
+    let critical_pid = if cfg!(target_family = "windows") { pid == 0 || pid == 4 } else { pid <= 1 };
+    critical_pid || CRITICAL_NAMES.iter().any(|n| n.eq_ignore_ascii_case(name))
+}
+
+// broad kinds of failure for process actions, each with its own advice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    Permission, // someone else's process
+    Gone,       // exited between the refresh and the click
+    Unsupported,
+    Other,
+}
+
+#[cfg(target_family = "unix")]
+const NO_SUCH_PROCESS: i32 = nix::errno::Errno::ESRCH as i32;
+// OpenProcess turns down a pid that no longer exists as an invalid parameter
+#[cfg(target_family = "windows")]
+const NO_SUCH_PROCESS: i32 = 87;
+
+pub fn classify_error(e: &std::io::Error) -> ErrorClass {
+    match e.kind() {
+        std::io::ErrorKind::PermissionDenied => ErrorClass::Permission,
+        std::io::ErrorKind::NotFound => ErrorClass::Gone,
+        std::io::ErrorKind::Unsupported => ErrorClass::Unsupported,
+        _ if e.raw_os_error() == Some(NO_SUCH_PROCESS) => ErrorClass::Gone,
+        _ => ErrorClass::Other,
+    }
+}
+
+// the error as the user should read it, with what to do about it where
+// there is something
+pub fn explain_error(e: &std::io::Error) -> String {
+    match classify_error(e) {
+        ErrorClass::Permission => {
+            let who = if cfg!(target_family = "windows") { "administrator" } else { "root" };
+            format!("permission denied, run as {} to manage this process", who)
+        }
+        ErrorClass::Gone => "the process has already exited".to_string(),
+        ErrorClass::Unsupported | ErrorClass::Other => e.to_string(),
+    }
+}
+
+// true until the process (the same one, not a reused pid) is gone or a zombie
+fn still_running(sys: &mut sysinfo::System, pid: i32, start_time: u64) -> bool {
+    let spid = sysinfo::Pid::from_u32(pid as u32);
+    sys.refresh_process(spid);
+    sys.process(spid)
+        .is_some_and(|p| p.start_time() == start_time && p.status() != sysinfo::ProcessStatus::Zombie)
+}
+
+// polls until the process is gone, false if it outlived timeout
+fn wait_for_exit(sys: &mut sysinfo::System, pid: i32, start_time: u64, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while still_running(sys, pid, start_time) {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    true
+}
+
+// how long a restarted process gets to shut down cleanly before it's killed
+pub const RESTART_GRACE: Duration = Duration::from_secs(5);
+
+// asks the process to exit, kills it if it is still there after
+// RESTART_GRACE, then launches argv again through start() in cwd with the
+// old environment. Blocks for up to fifteen seconds, so call it off the UI
+// thread
+pub fn restart(
+    pid: i32,
+    start_time: u64,
+    argv: &[String],
+    cwd: Option<&Path>,
+    env: &[(String, String)],
+) -> std::io::Result<()> {
+    if argv.is_empty() {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "no command line to restart with"));
+    }
+    // it may exit on its own at any point, that's what we wanted anyway
+    let unless_gone = |result: std::io::Result<()>| match result {
+        Err(e) if classify_error(&e) != ErrorClass::Gone => Err(e),
+        _ => Ok(()),
+    };
+    unless_gone(terminate(pid))?;
+
+    let mut sys = sysinfo::System::new();
+    if !wait_for_exit(&mut sys, pid, start_time, RESTART_GRACE) {
+        unless_gone(kill(pid))?;
+        if !wait_for_exit(&mut sys, pid, start_time, Duration::from_secs(10)) {
+            return Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "process did not exit in time"));
+        }
+    }
+
+    // detached, a restarted service shouldn't die with the monitor
+    start(&shell_join(argv), cwd, env, true)?;
+    Ok(())
+}
+
+// argv as one command line for start(), each argument quoted for sh
+#[cfg(target_family = "unix")]
+pub fn shell_join(argv: &[String]) -> String {
+    let quote = |arg: &String| {
+        let plain = !arg.is_empty()
+            && arg.chars().all(|c| c.is_ascii_alphanumeric() || "_-./=:,+@%".contains(c));
+        if plain {
+            arg.clone()
+        } else {
+            format!("'{}'", arg.replace('\'', "'\\''"))
+        }
+    };
+    argv.iter().map(quote).collect::<Vec<_>>().join(" ")
+}
+
+// argv as one command line for start(); cmd.exe has no real quoting, so
+// arguments with spaces or quotes are wrapped the way CommandLineToArgvW
+// reads them back
+#[cfg(target_family = "windows")]
+pub fn shell_join(argv: &[String]) -> String {
+    let quote = |arg: &String| {
+        if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+            arg.clone()
+        } else {
+            format!("\"{}\"", arg.replace('"', "\\\""))
+        }
+    };
+    argv.iter().map(quote).collect::<Vec<_>>().join(" ")
+}
+
+// signals offered in the row menu
+#[cfg(target_family = "unix")]
+pub const COMMON_SIGNALS: [SignalChoice; 7] = [
+    SignalChoice { num: libc::SIGHUP, name: "SIGHUP" },
+    SignalChoice { num: libc::SIGINT, name: "SIGINT" },
+    SignalChoice { num: libc::SIGQUIT, name: "SIGQUIT" },
+    SignalChoice { num: libc::SIGTERM, name: "SIGTERM" },
+    SignalChoice { num: libc::SIGUSR1, name: "SIGUSR1" },
+    SignalChoice { num: libc::SIGUSR2, name: "SIGUSR2" },
+    SignalChoice { num: libc::SIGKILL, name: "SIGKILL" },
+];
+
+#[cfg(target_family = "unix")]
+pub fn send_signal(pid: i32, signal: nix::sys::signal::Signal) -> std::io::Result<()> {
+    nix::sys::signal::kill(nix::unistd::Pid::from_raw(pid), signal)
+        .map_err(std::io::Error::from)
+}
+
+// signals whose default action ends the process, so they get the same
+// critical-process confirmation as a kill
+#[cfg(target_family = "unix")]
+pub fn is_terminating_signal(signum: i32) -> bool {
+    [libc::SIGKILL, libc::SIGTERM, libc::SIGQUIT, libc::SIGHUP, libc::SIGINT, libc::SIGABRT].contains(&signum)
+}
+
+// validates a raw signal number before sending it
+#[cfg(target_family = "unix")]
+pub fn send_raw_signal(pid: i32, signum: i32) -> std::io::Result<()> {
+    let signal = nix::sys::signal::Signal::try_from(signum).map_err(|_| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("invalid signal number {}", signum),
+        )
+    })?;
+    send_signal(pid, signal)
+}
+
+// tells a parent one of its children exited, so it calls wait() on zombies
+#[cfg(target_family = "unix")]
+pub fn reap_children(pid: i32) -> std::io::Result<()> {
+    send_signal(pid, nix::sys::signal::Signal::SIGCHLD)
 }
 
 #[cfg(target_family = "unix")]
@@ -60,7 +298,7 @@ pub fn suspend(pid: i32) -> std::io::Result<()> {
         nix::unistd::Pid::from_raw(pid),
         nix::sys::signal::Signal::SIGSTOP,
     )
-    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+    .map_err(std::io::Error::from)
 }
 
 #[cfg(target_family = "unix")]
@@ -69,22 +307,17 @@ pub fn resume(pid: i32) -> std::io::Result<()> {
         nix::unistd::Pid::from_raw(pid),
         nix::sys::signal::Signal::SIGCONT,
     )
-    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+    .map_err(std::io::Error::from)
 }
 
 #[cfg(target_family = "unix")]
-pub fn priority_boost(pid: i32) -> std::io::Result<()> {
-    use libc::{getpriority, setpriority, PRIO_PROCESS};
+pub fn set_priority(pid: i32, nice: i32) -> std::io::Result<()> {
+    use libc::{setpriority, PRIO_PROCESS};
     use std::io;
 
+    let nice = nice.clamp(NICE_MIN, NICE_MAX);
     unsafe {
-        let current = getpriority(PRIO_PROCESS, pid as u32);
-        let mut new_nice = current - 1; 
-        if new_nice < -20 {
-            new_nice = -20;
-        }
-
-        if setpriority(PRIO_PROCESS, pid as u32, new_nice) != 0 {
+        if setpriority(PRIO_PROCESS, pid as u32, nice) != 0 {
             return Err(io::Error::last_os_error());
         }
     }
@@ -92,33 +325,174 @@ pub fn priority_boost(pid: i32) -> std::io::Result<()> {
     Ok(())
 }
 
+// None when the nice value can't be read (process gone or access denied)
+#[cfg(target_family = "unix")]
+pub fn get_priority(pid: i32) -> Option<i32> {
+    use libc::{getpriority, PRIO_PROCESS};
+    use nix::errno::Errno;
+
+    // -1 is a valid nice value, so errno is the only way to detect failure
+    Errno::clear();
+    let nice = unsafe { getpriority(PRIO_PROCESS, pid as u32) };
+    if nice == -1 && Errno::last_raw() != 0 {
+        return None;
+    }
+    Some(nice)
+}
+
+#[cfg(target_family = "unix")]
+pub fn priority_boost(pid: i32) -> std::io::Result<()> {
+    use libc::{getpriority, PRIO_PROCESS};
+
+    let current = unsafe { getpriority(PRIO_PROCESS, pid as u32) };
+    set_priority(pid, current - 1)
+}
+
 #[cfg(target_family = "unix")]
 pub fn priority_lower(pid: i32) -> std::io::Result<()> {
-    use libc::{getpriority, setpriority, PRIO_PROCESS};
-    use std::io;
+    use libc::{getpriority, PRIO_PROCESS};
+
+    let current = unsafe { getpriority(PRIO_PROCESS, pid as u32) };
+    set_priority(pid, current + 1)
+}
 
+// cores the process may run on
+#[cfg(target_os = "linux")]
+pub fn get_affinity(pid: i32) -> std::io::Result<Vec<usize>> {
     unsafe {
-        let current = getpriority(PRIO_PROCESS, pid as u32);
-        let mut new_nice = current + 1;
-        if new_nice > 19 {
-            new_nice = 19;
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(pid, std::mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+            return Err(std::io::Error::last_os_error());
         }
+        Ok((0..libc::CPU_SETSIZE as usize).filter(|&cpu| libc::CPU_ISSET(cpu, &set)).collect())
+    }
+}
 
-        if setpriority(PRIO_PROCESS, pid as u32, new_nice) != 0 {
-            return Err(io::Error::last_os_error());
+// pins the process to the given cores; an empty list is rejected up front
+// since the kernel would refuse it anyway
+#[cfg(target_os = "linux")]
+pub fn set_affinity(pid: i32, cpus: &[usize]) -> std::io::Result<()> {
+    if cpus.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "at least one core must be allowed",
+        ));
+    }
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_ZERO(&mut set);
+        for &cpu in cpus {
+            libc::CPU_SET(cpu, &mut set);
+        }
+        if libc::sched_setaffinity(pid, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+            return Err(std::io::Error::last_os_error());
         }
     }
-
     Ok(())
 }
 
+// swapped-out size from the VmSwap line of /proc/<pid>/status, in bytes
+pub fn parse_vm_swap(status: &str) -> Option<u64> {
+    let line = status.lines().find_map(|l| l.strip_prefix("VmSwap:"))?;
+    let kb: u64 = line.trim().strip_suffix("kB")?.trim().parse().ok()?;
+    Some(kb * 1024)
+}
+
+// None for kernel threads, which have no VmSwap line, and for processes
+// that are gone or unreadable
+#[cfg(target_os = "linux")]
+pub fn swap_bytes(pid: i32) -> Option<u64> {
+    parse_vm_swap(&std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?)
+}
+
+// what one pass over a process's fd table found
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FdScan {
+    pub open: usize,
+    pub connected: usize, // sockets in the connected set passed to scan_fds
+}
+
+// counts /proc/<pid>/fd, and when given the connected socket inodes also
+// resolves each link to see which it holds, so the fd count and procnet
+// share one directory walk. None when the table can't be read, which is
+// usually another user's process
+#[cfg(target_os = "linux")]
+pub fn scan_fds(pid: i32, connected: Option<&std::collections::HashSet<u64>>) -> Option<FdScan> {
+    let entries = std::fs::read_dir(format!("/proc/{}/fd", pid)).ok()?;
+    let Some(connected) = connected else {
+        return Some(FdScan { open: entries.count(), connected: 0 });
+    };
+    let mut scan = FdScan::default();
+    for entry in entries.flatten() {
+        scan.open += 1;
+        let inode = std::fs::read_link(entry.path()).ok().and_then(|target| {
+            target.to_str()?.strip_prefix("socket:[")?.strip_suffix(']')?.parse::<u64>().ok()
+        });
+        if inode.is_some_and(|i| connected.contains(&i)) {
+            scan.connected += 1;
+        }
+    }
+    Some(scan)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn scan_fds(_pid: i32, _connected: Option<&std::collections::HashSet<u64>>) -> Option<FdScan> {
+    None
+}
+
+// sysinfo reports zero I/O when /proc/<pid>/io can't be read, which is the
+// case for other users' processes unless we're root
+#[cfg(target_os = "linux")]
+pub fn io_visible(pid: i32) -> bool {
+    std::fs::File::open(format!("/proc/{}/io", pid)).is_ok()
+}
+
+// elsewhere there's no cheap way to tell, so the counters are taken as is
+#[cfg(not(target_os = "linux"))]
+pub fn io_visible(_pid: i32) -> bool {
+    true
+}
+
+// no per-process swap figure elsewhere
+#[cfg(not(target_os = "linux"))]
+pub fn swap_bytes(_pid: i32) -> Option<u64> {
+    None
+}
+
+// runs cmd through the shell, optionally in cwd and with extra env vars;
+// None for a blank command
+// detached runs it in a new session with no terminal, so neither closing the
+// monitor nor its terminal hangs it up
 #[cfg(target_family = "unix")]
-pub fn start(cmd: &str) -> std::io::Result<()> {
+pub fn start(
+    cmd: &str,
+    cwd: Option<&Path>,
+    env: &[(String, String)],
+    detached: bool,
+) -> std::io::Result<Option<Child>> {
+    use std::os::unix::process::CommandExt;
+
     if cmd.trim().is_empty() {
-        return Ok(());
+        return Ok(None);
     }
-    Command::new("sh").arg("-c").arg(cmd).spawn()?;
-    Ok(())
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(cmd).envs(env.iter().map(|(k, v)| (k, v)));
+    if let Some(dir) = cwd {
+        command.current_dir(dir);
+    }
+    if detached {
+        command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+        // setsid is async-signal-safe, fine between fork and exec
+        unsafe {
+            command.pre_exec(|| {
+                if libc::setsid() == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+    command.spawn().map(Some)
 }
 
 #[cfg(target_family = "windows")]
@@ -141,6 +515,12 @@ pub fn kill(pid: i32) -> std::io::Result<()> {
     Ok(())
 }
 
+// windows has no graceful equivalent for arbitrary processes
+#[cfg(target_family = "windows")]
+pub fn terminate(pid: i32) -> std::io::Result<()> {
+    kill(pid)
+}
+
 #[cfg(target_family = "windows")]
 pub fn suspend(pid: i32) -> std::io::Result<()> {
     let threads = get_threads_in_process(pid)?;
@@ -208,11 +588,140 @@ pub fn priority_lower(pid: i32) -> std::io::Result<()> {
     Ok(())
 }
 
+// maps a unix style nice value onto the closest windows priority class
 #[cfg(target_family = "windows")]
-pub fn start(cmd: &str) -> std::io::Result<()> {
-    if cmd.trim().is_empty() { 
-        return Ok(()); 
+fn nice_to_priority_class(nice: i32) -> u32 {
+    match nice.clamp(NICE_MIN, NICE_MAX) {
+        -20..=-11 => HIGH_PRIORITY_CLASS,
+        -10..=-1 => ABOVE_NORMAL_PRIORITY_CLASS,
+        0 => NORMAL_PRIORITY_CLASS,
+        1..=10 => BELOW_NORMAL_PRIORITY_CLASS,
+        _ => IDLE_PRIORITY_CLASS,
+    }
+}
+
+#[cfg(target_family = "windows")]
+pub fn set_priority(pid: i32, nice: i32) -> std::io::Result<()> {
+    use windows_sys::Win32::System::Threading::OpenProcess;
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_SET_INFORMATION, 0, pid as u32);
+        if handle == std::ptr::null_mut() {
+            return Err(std::io::Error::last_os_error());
+        }
+        let ok = SetPriorityClass(handle, nice_to_priority_class(nice));
+        CloseHandle(handle);
+        if ok == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
     }
-    std::process::Command::new("cmd").arg("/C").arg(cmd).spawn()?;
     Ok(())
+}
+
+#[cfg(target_family = "windows")]
+pub fn set_priority_class(pid: i32, class: PriorityClass) -> std::io::Result<()> {
+    use windows_sys::Win32::System::Threading::{OpenProcess, REALTIME_PRIORITY_CLASS};
+
+    let raw = match class {
+        PriorityClass::Idle => IDLE_PRIORITY_CLASS,
+        PriorityClass::BelowNormal => BELOW_NORMAL_PRIORITY_CLASS,
+        PriorityClass::Normal => NORMAL_PRIORITY_CLASS,
+        PriorityClass::AboveNormal => ABOVE_NORMAL_PRIORITY_CLASS,
+        PriorityClass::High => HIGH_PRIORITY_CLASS,
+        PriorityClass::Realtime => REALTIME_PRIORITY_CLASS,
+    };
+    unsafe {
+        let handle = OpenProcess(PROCESS_SET_INFORMATION, 0, pid as u32);
+        if handle == std::ptr::null_mut() {
+            return Err(std::io::Error::last_os_error());
+        }
+        let ok = SetPriorityClass(handle, raw);
+        CloseHandle(handle);
+        if ok == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_family = "windows")]
+pub fn get_priority_class(pid: i32) -> Option<PriorityClass> {
+    use windows_sys::Win32::System::Threading::{
+        GetPriorityClass, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION, REALTIME_PRIORITY_CLASS,
+    };
+
+    let class = unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid as u32);
+        if handle == std::ptr::null_mut() {
+            return None;
+        }
+        let class = GetPriorityClass(handle);
+        CloseHandle(handle);
+        class
+    };
+
+    match class {
+        REALTIME_PRIORITY_CLASS => Some(PriorityClass::Realtime),
+        HIGH_PRIORITY_CLASS => Some(PriorityClass::High),
+        ABOVE_NORMAL_PRIORITY_CLASS => Some(PriorityClass::AboveNormal),
+        NORMAL_PRIORITY_CLASS => Some(PriorityClass::Normal),
+        BELOW_NORMAL_PRIORITY_CLASS => Some(PriorityClass::BelowNormal),
+        IDLE_PRIORITY_CLASS => Some(PriorityClass::Idle),
+        _ => None,
+    }
+}
+
+// reports the priority class as the nice value it corresponds to
+#[cfg(target_family = "windows")]
+pub fn get_priority(pid: i32) -> Option<i32> {
+    get_priority_class(pid).map(PriorityClass::nice)
+}
+
+// nothing is sent on windows, but treat it as a kill to be safe
+#[cfg(target_family = "windows")]
+pub fn is_terminating_signal(_signum: i32) -> bool {
+    true
+}
+
+#[cfg(target_family = "windows")]
+pub fn send_raw_signal(_pid: i32, _signum: i32) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "signals are not supported on Windows",
+    ))
+}
+
+// windows has no zombies to reap
+#[cfg(target_family = "windows")]
+pub fn reap_children(_pid: i32) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "signals are not supported on Windows",
+    ))
+}
+
+#[cfg(target_family = "windows")]
+// detached gets no console and its own process group, so Ctrl+C or closing
+// the monitor's console doesn't reach it
+pub fn start(
+    cmd: &str,
+    cwd: Option<&Path>,
+    env: &[(String, String)],
+    detached: bool,
+) -> std::io::Result<Option<Child>> {
+    use std::os::windows::process::CommandExt;
+    use windows_sys::Win32::System::Threading::{CREATE_NEW_PROCESS_GROUP, DETACHED_PROCESS};
+
+    if cmd.trim().is_empty() { 
+        return Ok(None); 
+    }
+    let mut command = std::process::Command::new("cmd");
+    command.arg("/C").arg(cmd).envs(env.iter().map(|(k, v)| (k, v)));
+    if let Some(dir) = cwd {
+        command.current_dir(dir);
+    }
+    if detached {
+        command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+    }
+    command.spawn().map(Some)
 }
\ No newline at end of file
@@ -0,0 +1,60 @@
+// This file estimates per-process network rates on Linux (cargo feature "procnet")
+//
+// The kernel doesn't count traffic per process, so the system rate is split
+// across processes by how many connected sockets each one holds. Coarse, but
+// enough to see which process is doing the talking.
+
+use std::collections::HashSet;
+use std::fs;
+
+use crate::models::ProcRow;
+
+// rows are "sl local_address rem_address st tx_queue:rx_queue ... uid timeout
+// inode ...", state 01 is ESTABLISHED for tcp and connected for udp
+pub fn connected_inodes(table: &str) -> Vec<u64> {
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() > 9 && fields[3] == "01" {
+                fields[9].parse().ok().filter(|&inode| inode != 0)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+pub fn all_connected_inodes() -> HashSet<u64> {
+    let mut out = HashSet::new();
+    for table in ["tcp", "tcp6", "udp", "udp6"] {
+        if let Ok(text) = fs::read_to_string(format!("/proc/net/{}", table)) {
+            out.extend(connected_inodes(&text));
+        }
+    }
+    out
+}
+
+// shares of the system rate by socket count; unreadable processes stay None
+pub fn split_rate(counts: &[Option<usize>], rx_bps: f32, tx_bps: f32) -> Vec<Option<(u64, u64)>> {
+    let total: usize = counts.iter().flatten().sum();
+    counts
+        .iter()
+        .map(|n| {
+            n.map(|n| {
+                let share = if total == 0 { 0.0 } else { n as f32 / total as f32 };
+                ((rx_bps * share) as u64, (tx_bps * share) as u64)
+            })
+        })
+        .collect()
+}
+
+// fills net_rx_bps/net_tx_bps of every row from the system-wide rates;
+// counts are the connected sockets per row, from Snapshot::sockets
+pub fn attribute(rows: &mut [ProcRow], counts: &[Option<usize>], rx_bps: f32, tx_bps: f32) {
+    for (p, rates) in rows.iter_mut().zip(split_rate(counts, rx_bps, tx_bps)) {
+        p.net_rx_bps = rates.map(|(rx, _)| rx);
+        p.net_tx_bps = rates.map(|(_, tx)| tx);
+    }
+}
@@ -1,18 +1,54 @@
 // this file is a container with custom background
 
-use iced::{Color, Background};
-use iced::widget::{button, container, text_input};
+use iced::{Color, Background, Element};
+use iced::widget::{button, container, text_input, Container};
+
+use crate::models::ProcState;
+
+pub fn is_dark(theme: &iced::Theme) -> bool {
+    theme.extended_palette().is_dark
+}
+
+// moves a dark theme background most of the way to white
+pub fn lighten(c: Color) -> Color {
+    Color { r: 1.0 - (1.0 - c.r) * 0.2, g: 1.0 - (1.0 - c.g) * 0.2, b: 1.0 - (1.0 - c.b) * 0.2, a: c.a }
+}
+
+// darkens a bright text color so it reads on a light background
+pub fn darken(c: Color) -> Color {
+    Color { r: c.r * 0.55, g: c.g * 0.55, b: c.b * 0.55, a: c.a }
+}
+
+// green at 0, yellow at 0.5, red from 1 up; t is a value over its "hot" level
+pub fn heat_color(t: f32) -> Color {
+    let green = Color::from_rgb(0.4, 0.85, 0.4);
+    let yellow = Color::from_rgb(1.0, 0.85, 0.3);
+    let red = Color::from_rgb(1.0, 0.35, 0.3);
+    let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+    let (from, to, k) = if t < 0.5 { (green, yellow, t * 2.0) } else { (yellow, red, t * 2.0 - 1.0) };
+    Color::from_rgb(
+        from.r + (to.r - from.r) * k,
+        from.g + (to.g - from.g) * k,
+        from.b + (to.b - from.b) * k,
+    )
+}
 
 pub struct StaticBg {
-    pub bg: Color,
+    pub bg: Color, // dark theme color, the light theme uses a lightened one
 }
 
 impl container::StyleSheet for StaticBg {
     type Style = iced::Theme;
 
-    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+    fn appearance(&self, style: &Self::Style) -> container::Appearance {
+        let (bg, text_color) = if is_dark(style) {
+            (self.bg, None)
+        } else {
+            (lighten(self.bg), Some(Color::from_rgb(0.1, 0.1, 0.1)))
+        };
         container::Appearance {
-            background: Some(Background::Color(self.bg)),
+            background: Some(Background::Color(bg)),
+            text_color,
             border: iced::Border {
                 radius: 6.0.into(),
                 ..Default::default()
@@ -22,6 +58,41 @@ impl container::StyleSheet for StaticBg {
     }
 }
 
+// row background: selection wins, then stopped and zombie processes get flagged
+pub fn row_style(state: ProcState, selected: bool) -> iced::theme::Container {
+    let bg = if selected {
+        Color::from_rgb(0.2, 0.25, 0.35)
+    } else {
+        match state {
+            ProcState::Stopped => Color::from_rgb(0.35, 0.27, 0.08),
+            ProcState::Zombie => Color::from_rgb(0.35, 0.1, 0.1),
+            _ => return iced::theme::Container::Transparent,
+        }
+    };
+    iced::theme::Container::Custom(Box::new(StaticBg { bg }))
+}
+
+// colors the text inside it, darkened on the light theme
+pub struct TintedText {
+    pub color: Color,
+}
+
+impl container::StyleSheet for TintedText {
+    type Style = iced::Theme;
+
+    fn appearance(&self, style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            text_color: Some(if is_dark(style) { self.color } else { darken(self.color) }),
+            ..Default::default()
+        }
+    }
+}
+
+// wraps content so its text picks up a theme aware color
+pub fn tinted<'a, Message: 'a>(content: impl Into<Element<'a, Message>>, color: Color) -> Container<'a, Message> {
+    container(content).style(iced::theme::Container::Custom(Box::new(TintedText { color })))
+}
+
 pub struct StartButton;
 impl button::StyleSheet for StartButton {
     type Style = iced::Theme;
@@ -57,49 +128,75 @@ pub struct RoundedTextInput;
 impl text_input::StyleSheet for RoundedTextInput {
     type Style = iced::Theme;
 
-    fn active(&self, _style: &Self::Style) -> text_input::Appearance {
+    fn active(&self, style: &Self::Style) -> text_input::Appearance {
+        let dark = is_dark(style);
         text_input::Appearance {
-            background: Background::Color(Color::from_rgb(0.18, 0.18, 0.18)),
+            background: Background::Color(if dark {
+                Color::from_rgb(0.18, 0.18, 0.18)
+            } else {
+                Color::from_rgb(0.97, 0.97, 0.97)
+            }),
             border: iced::Border {
                 radius: 7.0.into(),
                 width: 1.0,
-                color: Color::from_rgb(0.3, 0.3, 0.3),
+                color: if dark { Color::from_rgb(0.3, 0.3, 0.3) } else { Color::from_rgb(0.75, 0.75, 0.75) },
             },
-            icon_color: Color::WHITE,
+            icon_color: self.value_color(style),
         }
     }
 
-    fn focused(&self, _style: &Self::Style) -> text_input::Appearance {
+    fn focused(&self, style: &Self::Style) -> text_input::Appearance {
+        let dark = is_dark(style);
         text_input::Appearance {
-            background: Background::Color(Color::from_rgb(0.22, 0.22, 0.22)),
+            background: Background::Color(if dark {
+                Color::from_rgb(0.22, 0.22, 0.22)
+            } else {
+                Color::WHITE
+            }),
             border: iced::Border {
                 radius: 7.0.into(),
                 width: 1.0,
                 color: Color::from_rgb(0.4, 0.4, 1.0),
             },
-            icon_color: Color::WHITE,
+            icon_color: self.value_color(style),
         }
     }
 
-    fn placeholder_color(&self, _style: &Self::Style) -> Color {
-        Color::from_rgb(0.6, 0.6, 0.6)
+    fn placeholder_color(&self, style: &Self::Style) -> Color {
+        if is_dark(style) {
+            Color::from_rgb(0.6, 0.6, 0.6)
+        } else {
+            Color::from_rgb(0.45, 0.45, 0.45)
+        }
     }
 
-    fn value_color(&self, _style: &Self::Style) -> Color {
-        Color::WHITE
+    fn value_color(&self, style: &Self::Style) -> Color {
+        if is_dark(style) {
+            Color::WHITE
+        } else {
+            Color::BLACK
+        }
     }
 
-    fn selection_color(&self, _style: &Self::Style) -> Color {
-        Color::from_rgb(0.4, 0.4, 1.0)
+    fn selection_color(&self, style: &Self::Style) -> Color {
+        if is_dark(style) {
+            Color::from_rgb(0.4, 0.4, 1.0)
+        } else {
+            Color::from_rgb(0.7, 0.75, 1.0)
+        }
     }
 
     fn disabled_color(&self, _style: &Self::Style) -> Color {
         Color::from_rgb(0.5, 0.5, 0.5)
     }
 
-    fn disabled(&self, _style: &Self::Style) -> text_input::Appearance {
+    fn disabled(&self, style: &Self::Style) -> text_input::Appearance {
         text_input::Appearance {
-            background: Background::Color(Color::from_rgb(0.15, 0.15, 0.15)),
+            background: Background::Color(if is_dark(style) {
+                Color::from_rgb(0.15, 0.15, 0.15)
+            } else {
+                Color::from_rgb(0.9, 0.9, 0.9)
+            }),
             border: iced::Border {
                 radius: 6.0.into(),
                 width: 1.0,
@@ -110,6 +207,53 @@ impl text_input::StyleSheet for RoundedTextInput {
     }
 }
 
+// filter box with a red border, used for an invalid regex
+pub struct InvalidTextInput;
+
+impl text_input::StyleSheet for InvalidTextInput {
+    type Style = iced::Theme;
+
+    fn active(&self, style: &Self::Style) -> text_input::Appearance {
+        text_input::Appearance {
+            border: iced::Border {
+                color: Color::from_rgb(0.9, 0.25, 0.25),
+                ..RoundedTextInput.active(style).border
+            },
+            ..RoundedTextInput.active(style)
+        }
+    }
+
+    fn focused(&self, style: &Self::Style) -> text_input::Appearance {
+        text_input::Appearance {
+            border: iced::Border {
+                color: Color::from_rgb(1.0, 0.35, 0.35),
+                ..RoundedTextInput.focused(style).border
+            },
+            ..RoundedTextInput.focused(style)
+        }
+    }
+
+    fn placeholder_color(&self, style: &Self::Style) -> Color {
+        RoundedTextInput.placeholder_color(style)
+    }
+
+    fn value_color(&self, style: &Self::Style) -> Color {
+        RoundedTextInput.value_color(style)
+    }
+
+    fn selection_color(&self, style: &Self::Style) -> Color {
+        RoundedTextInput.selection_color(style)
+    }
+
+    fn disabled_color(&self, style: &Self::Style) -> Color {
+        RoundedTextInput.disabled_color(style)
+    }
+
+    fn disabled(&self, style: &Self::Style) -> text_input::Appearance {
+        RoundedTextInput.disabled(style)
+    }
+}
+
 // shared traits for buttons
 pub trait RoundedBase {
     fn base(&self, color: Color) -> button::Appearance {
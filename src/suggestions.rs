@@ -1,24 +1,90 @@
-use crate::models::{ProcRow, Suggestion};
-use crate::util::fmt_bytes;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 
-pub fn make_suggestions(rows: &[ProcRow], total_cpu: f32, mem_pct: f32) -> Vec<Suggestion> {
+use crate::models::{DiskUsage, GraphSeries, ProcRow, ProcState, Severity, Suggestion, SuggestionAction, SuggestionCategory, Thresholds};
+use crate::util::{fmt_bytes, fmt_duration, fmt_parent};
+
+// per-process cpu (percent of one core) that counts as busy for the
+// priority check
+const BUSY_PROC_CPU: f32 = 50.0;
+// a process stopped for longer than this was probably forgotten
+const LONG_STOPPED_SECS: u64 = 10 * 60;
+// well past what normal programs keep open and near the common 1024 soft
+// limit, which usually means descriptors are leaking
+pub const HIGH_OPEN_FILES: usize = 1000;
+// a mount this full is about to start failing writes
+pub const DISK_FULL_PERCENT: f32 = 90.0;
+// past this it's critical, logs and databases may already be failing
+const DISK_CRITICAL_PERCENT: f32 = 98.0;
+
+// samples of memory kept per process; it has to grow across all of them
+// before it looks like a leak
+pub const LEAK_SAMPLES: usize = 120;
+// smaller processes move around too much for a trend to mean anything
+pub const LEAK_MIN_BYTES: u64 = 200 * 1024 * 1024;
+// the fitted trend has to add at least this share of the starting size
+const LEAK_MIN_GROWTH: f32 = 0.2;
+
+// least squares slope, in units per sample
+pub fn trend_slope(points: &VecDeque<f32>) -> f32 {
+    let n = points.len() as f32;
+    if n < 2.0 {
+        return 0.0;
+    }
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = points.iter().sum::<f32>() / n;
+    let (mut num, mut den) = (0.0, 0.0);
+    for (i, &y) in points.iter().enumerate() {
+        let dx = i as f32 - mean_x;
+        num += dx * (y - mean_y);
+        den += dx * dx;
+    }
+    num / den
+}
+
+// a full window that never shrank and whose trend adds up to real growth
+fn looks_like_leak(points: &VecDeque<f32>) -> bool {
+    let (Some(&first), Some(&last)) = (points.front(), points.back()) else {
+        return false;
+    };
+    points.len() >= LEAK_SAMPLES
+        && last >= LEAK_MIN_BYTES as f32
+        && points.iter().zip(points.iter().skip(1)).all(|(a, b)| b >= a)
+        && trend_slope(points) * (points.len() - 1) as f32 >= first * LEAK_MIN_GROWTH
+}
+
+pub fn make_suggestions(
+    rows: &[ProcRow],
+    total_cpu: f32,
+    mem_pct: f32,
+    thresholds: &Thresholds,
+    mem_history: &HashMap<i32, GraphSeries>, // bytes per pid, oldest first
+    sample_secs: f32,
+    disks: &[DiskUsage],
+) -> Vec<Suggestion> {
     let mut out = Vec::new();
     
-    if total_cpu > 10.0 {
-        if let Some(top) = rows.iter().max_by(|a, b| a.cpu.total_cmp(&b.cpu)) {
+    // halted processes are left to the stopped and zombie suggestions below,
+    // so one that was just suspended isn't offered up as the cpu culprit
+    if total_cpu > thresholds.cpu_percent as f32 {
+        if let Some(top) = rows.iter().filter(|p| p.status.can_run()).max_by(|a, b| a.cpu.total_cmp(&b.cpu)) {
             out.push(Suggestion {
+                key: format!("cpu:{}", top.pid),
                 title: format!("High CPU: {} at {:.1}%", top.name, top.cpu),
                 detail: format!(
                     "Consider suspending or killing PID {} if it's misbehaving.",
                     top.pid
                 ),
+                severity: Severity::Critical,
+                category: SuggestionCategory::Cpu,
+                action: None,
             });
         }
     }
     
-    if mem_pct > 20.0 {
+    if mem_pct > thresholds.mem_percent as f32 {
         if let Some(top) = rows.iter().max_by_key(|p| p.mem_bytes) {
             out.push(Suggestion {
+                key: format!("mem:{}", top.pid),
                 title: format!(
                     "Memory pressure: {} using {}",
                     top.name,
@@ -28,18 +94,157 @@ pub fn make_suggestions(rows: &[ProcRow], total_cpu: f32, mem_pct: f32) -> Vec<S
                     "Close unused apps or lower priority of PID {}.",
                     top.pid
                 ),
+                severity: Severity::Critical,
+                category: SuggestionCategory::Memory,
+                action: None,
             });
         }
     }
     
+    for p in rows.iter() {
+        let Some(series) = mem_history.get(&p.pid).filter(|s| looks_like_leak(&s.points)) else {
+            continue;
+        };
+        let first = series.points.front().copied().unwrap_or_default() as u64;
+        let span = (series.points.len() - 1) as f32 * sample_secs;
+        out.push(Suggestion {
+            key: format!("leak:{}", p.pid),
+            title: format!(
+                "Possible leak: {} grew from {} to {} over {}",
+                p.name,
+                fmt_bytes(first),
+                fmt_bytes(p.mem_bytes),
+                fmt_duration(span as u64)
+            ),
+            detail: format!(
+                "PID {} hasn't released memory in that time; restarting it gets it back.",
+                p.pid
+            ),
+            severity: Severity::Warning,
+            category: SuggestionCategory::Memory,
+            action: None,
+        });
+    }
+
+    // unknown I/O isn't idle, another user's busy daemon would be flagged
     for p in rows.iter().filter(|p| {
-        p.cpu < 0.5 && (p.read_bps + p.write_bps) < 1024 && p.mem_bytes > 500 * 1024 * 1024
+        p.cpu < thresholds.idle_cpu_percent
+            && p.mem_bytes > thresholds.idle_mem_bytes
+            && matches!((p.read_bps, p.write_bps), (Some(r), Some(w)) if r + w < 1024)
     }) {
         out.push(Suggestion {
+            key: format!("idle:{}", p.pid),
             title: format!("Idle hog: {} holding {}", p.name, fmt_bytes(p.mem_bytes)),
             detail: format!("You could lower its priority or close it. PID {}", p.pid),
+            severity: Severity::Info,
+            category: SuggestionCategory::Idle,
+            action: None,
         });
     }
-    
+
+    // negative nice means someone raised it; busy at that level it starves
+    // everything else
+    for p in rows
+        .iter()
+        .filter(|p| p.status.can_run() && p.cpu >= BUSY_PROC_CPU && p.priority.is_some_and(|n| n < 0))
+    {
+        out.push(Suggestion {
+            key: format!("elevated:{}", p.pid),
+            title: format!("High CPU at elevated priority: {} at {:.1}%", p.name, p.cpu),
+            detail: format!(
+                "PID {} runs at nice {}; lowering it lets other processes keep up.",
+                p.pid,
+                p.priority.unwrap_or_default()
+            ),
+            severity: Severity::Warning,
+            category: SuggestionCategory::Cpu,
+            action: None,
+        });
+    }
+
+    for p in rows.iter().filter(|p| p.open_files.is_some_and(|n| n >= HIGH_OPEN_FILES)) {
+        out.push(Suggestion {
+            key: format!("fds:{}", p.pid),
+            title: format!("{} open files: {}", p.open_files.unwrap_or_default(), p.name),
+            detail: format!(
+                "PID {} may be leaking file descriptors; restarting it releases them.",
+                p.pid
+            ),
+            severity: Severity::Warning,
+            category: SuggestionCategory::Files,
+            action: None,
+        });
+    }
+
+    for p in rows.iter().filter(|p| p.status == ProcState::Stopped && p.stopped_secs >= LONG_STOPPED_SECS) {
+        out.push(Suggestion {
+            key: format!("stopped:{}", p.pid),
+            title: format!("Suspended for {}: {}", fmt_duration(p.stopped_secs), p.name),
+            detail: format!(
+                "PID {} is still holding {}. Resume it or kill it if it's no longer needed.",
+                p.pid,
+                fmt_bytes(p.mem_bytes)
+            ),
+            severity: Severity::Info,
+            category: SuggestionCategory::Stopped,
+            action: None,
+        });
+    }
+
+    // zombies are already dead, only their parent can clear them by reaping,
+    // so there's one suggestion per parent rather than per zombie
+    let mut zombies: BTreeMap<Option<i32>, (usize, Option<&str>)> = BTreeMap::new();
+    for p in rows.iter().filter(|p| p.status == ProcState::Zombie) {
+        let entry = zombies.entry(p.parent).or_default();
+        entry.0 += 1;
+        entry.1 = p.parent_name.as_deref();
+    }
+    for (parent, (count, parent_name)) in zombies {
+        let plural = if count == 1 { "" } else { "es" };
+        let suggestion = match parent {
+            Some(ppid) => Suggestion {
+                key: format!("zombie:{}", ppid),
+                title: format!(
+                    "{} zombie process{} under {}",
+                    count,
+                    plural,
+                    fmt_parent(Some(ppid), parent_name)
+                ),
+                detail: "Zombies can't be killed, they're cleared when the parent reaps them. \
+                    SIGCHLD may prompt it; if not, ending the parent hands them to init."
+                    .to_string(),
+                severity: Severity::Info,
+                category: SuggestionCategory::Zombie,
+                action: Some(SuggestionAction::SignalParent(ppid)),
+            },
+            None => Suggestion {
+                key: "zombie:orphan".to_string(),
+                title: format!("{} zombie process{} with no known parent", count, plural),
+                detail: "Zombies can't be killed, they're cleared when the parent reaps them.".to_string(),
+                severity: Severity::Info,
+                category: SuggestionCategory::Zombie,
+                action: None,
+            },
+        };
+        out.push(suggestion);
+    }
+
+    for d in disks.iter().filter(|d| d.used_percent() >= DISK_FULL_PERCENT) {
+        out.push(Suggestion {
+            key: format!("disk:{}", d.mount),
+            title: format!("Disk nearly full: {} at {:.0}%", d.mount, d.used_percent()),
+            detail: format!(
+                "{} free of {}. Clear space before writes start failing.",
+                fmt_bytes(d.available),
+                fmt_bytes(d.total)
+            ),
+            severity: if d.used_percent() >= DISK_CRITICAL_PERCENT { Severity::Critical } else { Severity::Warning },
+            category: SuggestionCategory::Disk,
+            action: None,
+        });
+    }
+
+    // stable, so equal severities keep the order they were found in
+    out.sort_by_key(|s| std::cmp::Reverse(s.severity));
     out
 }
\ No newline at end of file
@@ -1,33 +1,566 @@
-use sysinfo::{System, Networks};
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+use sysinfo::{Components, Disks, Networks, Pid, ProcessStatus, System, Uid, Users};
 
-// this calculates transfer rate
-pub fn bytes_per_sec(prev: u64, now: u64, dt_s: f32) -> f32 {
-    if now >= prev {
-        (now - prev) as f32 / dt_s
-    } else {
-        0.0 // handle counter reset
+use crate::gpu::{GpuMonitor, GpuSample};
+use crate::models::{
+    ColumnId, DiskUsage, GraphSeries, InterfaceRate, IoKey, IoSnapshot, ProcDetails, ProcRow, ProcState, SettingsModel,
+    SortKey, StateCounts, SystemStats,
+};
+use crate::platform;
+
+// capacity barely moves and asking every mount for it is slow, so disks are
+// refreshed once every this many samples
+pub const DISK_REFRESH_SAMPLES: u32 = 10;
+// every fd table is walked this often for the open files suggestion; in
+// between only the rows on screen are counted and the rest keep their last
+// count
+pub const FD_SCAN_SAMPLES: u32 = 10;
+
+// longest gap a rate is trusted over; past it (timer throttled while
+// minimized, a suspended laptop) the average says little about the present
+pub const MAX_RATE_DT: f32 = 10.0;
+// floor for the divisor so back-to-back samples can't blow a rate up
+pub const MIN_RATE_DT: f32 = 0.001;
+
+// a counter that falls below this share of its previous reading was reset
+// (process exec'd, device reconnected); a smaller dip is a read glitch
+pub const COUNTER_RESET_FRACTION: f64 = 0.5;
+
+pub fn is_counter_reset(prev: u64, now: u64) -> bool {
+    (now as f64) < prev as f64 * COUNTER_RESET_FRACTION
+}
+
+// transfer rate between two readings of a cumulative counter. None when the
+// gap is too long to mean anything, or on a reset, since the bytes moved
+// since the previous reading can't be known. A glitchy small dip reads as 0
+pub fn bytes_per_sec(prev: u64, now: u64, dt_s: f32) -> Option<f32> {
+    if dt_s.is_nan() || dt_s > MAX_RATE_DT || is_counter_reset(prev, now) {
+        return None;
+    }
+    Some(now.saturating_sub(prev) as f32 / dt_s.max(MIN_RATE_DT))
+}
+
+// rate shown for a row; across a reset the last rate is carried forward one
+// sample rather than dipping to 0, the next sample measures from the new base
+pub fn io_rate(prev: u64, now: u64, dt_s: f32, last_rate: u64) -> u64 {
+    match bytes_per_sec(prev, now, dt_s) {
+        Some(rate) => rate as u64,
+        None if is_counter_reset(prev, now) => last_rate,
+        None => 0,
+    }
+}
+
+// drops entries for every (pid, start time) not in live, so per-process
+// maps can't grow without bound over a long session
+pub fn prune_dead<T>(map: &mut HashMap<IoKey, T>, live: &[ProcRow]) {
+    let keys: HashSet<IoKey> = live.iter().map(|p| (p.pid, p.start_time)).collect();
+    map.retain(|key, _| keys.contains(key));
+}
+
+// exponential moving average; alpha is the weight of the newest sample and
+// the first sample starts the average as is
+pub fn ema(prev: Option<f32>, sample: f32, alpha: f32) -> f32 {
+    match prev {
+        Some(prev) => alpha * sample + (1.0 - alpha) * prev,
+        None => sample,
+    }
+}
+
+// same for the per-row cpu and memory history
+pub fn prune_row_history(history: &mut HashMap<i32, GraphSeries>, live: &[ProcRow]) {
+    let pids: HashSet<i32> = live.iter().map(|p| p.pid).collect();
+    history.retain(|pid, _| pids.contains(pid));
+}
+
+// builds one row per process, updating the per-process I/O baselines, and
+// counts the processes in each state
+pub fn collect_rows(
+    sys: &System,
+    users: &Users,
+    my_uid: Option<&Uid>,
+    last_io: &mut HashMap<IoKey, IoSnapshot>,
+    dt: f32,
+    rows: &mut Vec<ProcRow>,
+) -> StateCounts {
+    let now_secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let total_mem = sys.total_memory().max(1);
+    let mut states = StateCounts::default();
+    rows.clear();
+    for (pid, proc_) in sys.processes() {
+        let pid_i32 = pid.as_u32() as i32;
+        let name = proc_.name().to_string();
+        // owner lookup can fail (e.g. on Windows), so fall back to empty
+        let user = proc_
+            .user_id()
+            .and_then(|uid| users.get_user_by_id(uid))
+            .map(|u| u.name().to_string())
+            .unwrap_or_default();
+        let mine = my_uid.is_some() && proc_.user_id() == my_uid;
+        let priority = platform::get_priority(pid_i32);
+        let cpu = proc_.cpu_usage();
+        let mem_bytes = proc_.memory();
+        let status = proc_state(proc_.status());
+        states.add(status);
+        let start_time = proc_.start_time();
+        // keyed with the start time so a recycled pid starts from its own
+        // counters instead of the dead process's
+        let io_key = (pid_i32, start_time);
+        let io = proc_.disk_usage();
+        let prev = last_io
+            .get(&io_key)
+            .copied()
+            .unwrap_or_else(|| IoSnapshot {
+                read: io.total_read_bytes,
+                write: io.total_written_bytes,
+                // checked once per process rather than every sample
+                visible: platform::io_visible(pid_i32),
+                ..Default::default()
+            });
+        // a row needs some value; Snapshot::rates_valid keeps a stale
+        // interval off the graphs
+        let read = io_rate(prev.read, io.total_read_bytes, dt, prev.read_bps);
+        let write = io_rate(prev.write, io.total_written_bytes, dt, prev.write_bps);
+        let read_bps = prev.visible.then_some(read);
+        let write_bps = prev.visible.then_some(write);
+        last_io.insert(
+            io_key,
+            IoSnapshot {
+                read: io.total_read_bytes,
+                write: io.total_written_bytes,
+                read_bps: read,
+                write_bps: write,
+                visible: prev.visible,
+            },
+        );
+
+        rows.push(ProcRow {
+            pid: pid_i32,
+            name,
+            user,
+            priority,
+            cpu,
+            mem_bytes,
+            mem_percent: (mem_bytes as f64 * 100.0 / total_mem as f64) as f32,
+            // filled in by Monitor when wanted, see ExtraReads
+            swap_bytes: None,
+            read_bps,
+            write_bps,
+            read_total: prev.visible.then_some(io.total_read_bytes),
+            write_total: prev.visible.then_some(io.total_written_bytes),
+            status,
+            start_time,
+            threads: proc_.tasks().map_or(0, |t| t.len()),
+            open_files: None, // see ExtraReads
+            // saturating so a start time slightly in the future reads 0s
+            uptime_secs: now_secs.saturating_sub(start_time),
+            mine,
+            parent: proc_.parent().map(|p| p.as_u32() as i32),
+            parent_name: parent_name(sys, proc_),
+            restartable: !proc_.cmd().is_empty(),
+            stopped_secs: 0,
+            // filled in by procnet where supported
+            net_rx_bps: None,
+            net_tx_bps: None,
+        });
+    }
+    prune_dead(last_io, rows);
+    states
+}
+
+// per-process reads that cost a file read each, so they're only done while
+// something shows them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExtraReads {
+    pub swap: bool, // every row
+    pub open_files: bool, // every row, every sample
+    pub selected: Option<i32>, // this row gets everything regardless
+}
+
+impl ExtraReads {
+    // what the table as configured needs: visible or sorted columns
+    pub fn for_settings(settings: &SettingsModel, selected: Option<i32>) -> Self {
+        ExtraReads {
+            swap: settings.columns.contains(&ColumnId::Swap) || settings.sort_key == SortKey::Swap,
+            open_files: settings.columns.contains(&ColumnId::OpenFiles) || settings.sort_key == SortKey::OpenFiles,
+            selected,
+        }
+    }
+}
+
+// everything one sample saw; rates cover the time since the previous one
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub rows: Vec<ProcRow>,
+    pub stats: SystemStats,
+    pub net_rates: Vec<InterfaceRate>,
+    pub disk_read_bps: f32,
+    pub disk_write_bps: f32,
+    pub temperature: Option<f32>, // hottest sensor, None without sensors
+    pub gpu: Option<GpuSample>,
+    pub dt: f32, // seconds since the previous sample
+    pub rates_valid: bool, // false when dt is past MAX_RATE_DT, skip plotting rates
+    pub disks: Vec<DiskUsage>, // as of the last disk refresh, see DISK_REFRESH_SAMPLES
+    #[cfg(all(feature = "procnet", target_os = "linux"))]
+    pub sockets: Vec<Option<usize>>, // connected sockets per row, for procnet::attribute
+}
+
+// the data side of the app with no UI attached: owns the sysinfo handles
+// and the per-process baselines that rates and smoothing need between
+// samples. Call sample() on a steady interval
+#[derive(Debug)]
+pub struct Monitor {
+    sys: System,
+    networks: Networks,
+    components: Components,
+    gpu: Option<GpuMonitor>, // None without a supported GPU
+    users: Users,
+    my_uid: Option<Uid>, // owner of this process, for ProcRow::mine
+    last_io: HashMap<IoKey, IoSnapshot>,
+    cpu_ema: HashMap<IoKey, f32>, // smoothed per-process cpu, only while smoothing is on
+    total_cpu_ema: Option<f32>,
+    stopped_since: HashMap<IoKey, Instant>, // first sample each stopped process was seen stopped
+    cpu_alpha: Option<f32>, // smoothing weight, None for raw readings
+    last_ts: Instant,
+    disks: Disks,
+    disk_usage: Vec<DiskUsage>,
+    disks_due_in: u32, // samples until the next disk refresh
+    extra: ExtraReads,
+    fd_counts: HashMap<IoKey, usize>, // last count per process, stands in between scans
+    fds_due_in: u32, // samples until every fd table is walked again
+}
+
+impl Default for Monitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Monitor {
+    pub fn new() -> Self {
+        let mut sys = System::new_all();
+        sys.refresh_all();
+        let my_uid = sysinfo::get_current_pid()
+            .ok()
+            .and_then(|pid| sys.process(pid))
+            .and_then(|p| p.user_id().cloned());
+        Monitor {
+            sys,
+            networks: Networks::new_with_refreshed_list(),
+            components: Components::new_with_refreshed_list(),
+            gpu: GpuMonitor::new(),
+            users: Users::new_with_refreshed_list(),
+            my_uid,
+            last_io: HashMap::new(),
+            cpu_ema: HashMap::new(),
+            total_cpu_ema: None,
+            stopped_since: HashMap::new(),
+            cpu_alpha: None,
+            last_ts: Instant::now(),
+            disks: Disks::new(),
+            disk_usage: Vec::new(),
+            disks_due_in: 0,
+            extra: ExtraReads::default(),
+            fd_counts: HashMap::new(),
+            fds_due_in: 0,
+        }
+    }
+
+    // the underlying sysinfo state as of the last sample, for per-process
+    // lookups rows don't carry (command line, cwd, ...)
+    pub fn system(&self) -> &System {
+        &self.sys
+    }
+
+    pub fn has_gpu(&self) -> bool {
+        self.gpu.is_some()
+    }
+
+    // Some(alpha) smooths cpu readings with an EMA, None reports them raw
+    pub fn set_cpu_smoothing(&mut self, alpha: Option<f32>) {
+        self.cpu_alpha = alpha;
+        if alpha.is_none() {
+            // start over from raw values next time it's switched on
+            self.cpu_ema.clear();
+            self.total_cpu_ema = None;
+        }
+    }
+
+    pub fn set_extra_reads(&mut self, extra: ExtraReads) {
+        self.extra = extra;
+    }
+
+    pub fn sample(&mut self) -> Snapshot {
+        self.sample_into(Vec::new())
+    }
+
+    // same as sample but fills a previous snapshot's rows buffer instead of
+    // allocating a fresh one
+    pub fn sample_into(&mut self, mut rows: Vec<ProcRow>) -> Snapshot {
+        self.sys.refresh_all();
+        self.networks.refresh();
+        self.components.refresh();
+        // relisted too, so mounts that come and go show up
+        if self.disks_due_in == 0 {
+            self.disks.refresh_list();
+            self.disk_usage = disk_usage(&self.disks);
+            self.disks_due_in = DISK_REFRESH_SAMPLES;
+        }
+        self.disks_due_in -= 1;
+
+        let now = Instant::now();
+        let dt = now.duration_since(self.last_ts).as_secs_f32().max(MIN_RATE_DT);
+        self.last_ts = now;
+
+        let mut stats = system_stats(&self.sys);
+        stats.states = collect_rows(&self.sys, &self.users, self.my_uid.as_ref(), &mut self.last_io, dt, &mut rows);
+        #[cfg_attr(not(all(feature = "procnet", target_os = "linux")), allow(unused_variables))]
+        let sockets = self.read_extras(&mut rows);
+        if let Some(alpha) = self.cpu_alpha {
+            let smoothed = ema(self.total_cpu_ema, stats.cpu_percent, alpha);
+            self.total_cpu_ema = Some(smoothed);
+            stats.cpu_percent = smoothed;
+            for p in rows.iter_mut() {
+                let key = (p.pid, p.start_time);
+                p.cpu = ema(self.cpu_ema.get(&key).copied(), p.cpu, alpha);
+                self.cpu_ema.insert(key, p.cpu);
+            }
+            prune_dead(&mut self.cpu_ema, &rows);
+        }
+
+        // resuming or exiting restarts the clock
+        let mut still_stopped = HashSet::new();
+        for p in rows.iter_mut().filter(|p| p.status == ProcState::Stopped) {
+            let key = (p.pid, p.start_time);
+            let since = *self.stopped_since.entry(key).or_insert(now);
+            p.stopped_secs = now.duration_since(since).as_secs();
+            still_stopped.insert(key);
+        }
+        self.stopped_since.retain(|key, _| still_stopped.contains(key));
+
+        let (disk_read_bps, disk_write_bps) = disk_rates(&rows);
+        Snapshot {
+            rows,
+            stats,
+            net_rates: interface_rates(&self.networks, dt),
+            disk_read_bps,
+            disk_write_bps,
+            temperature: max_temperature(&self.components),
+            gpu: self.gpu.as_ref().and_then(GpuMonitor::sample),
+            dt,
+            rates_valid: dt <= MAX_RATE_DT,
+            disks: self.disk_usage.clone(),
+            #[cfg(all(feature = "procnet", target_os = "linux"))]
+            sockets,
+        }
+    }
+
+    // reads swap and fd counts for the rows that need them; returns the
+    // connected sockets per row when procnet is on
+    fn read_extras(&mut self, rows: &mut [ProcRow]) -> Vec<Option<usize>> {
+        let extra = self.extra;
+        // procnet needs every socket each sample, the fd count comes with it
+        let procnet = cfg!(all(feature = "procnet", target_os = "linux"));
+        let full_scan = procnet || self.fds_due_in == 0;
+        if self.fds_due_in == 0 {
+            self.fds_due_in = FD_SCAN_SAMPLES;
+        }
+        self.fds_due_in -= 1;
+        #[cfg(all(feature = "procnet", target_os = "linux"))]
+        let connected = Some(crate::procnet::all_connected_inodes());
+        #[cfg(not(all(feature = "procnet", target_os = "linux")))]
+        let connected: Option<HashSet<u64>> = None;
+
+        let mut sockets = Vec::new();
+        for p in rows.iter_mut() {
+            let wanted = extra.selected == Some(p.pid);
+            if extra.swap || wanted {
+                p.swap_bytes = platform::swap_bytes(p.pid);
+            }
+            let key = (p.pid, p.start_time);
+            if full_scan || extra.open_files || wanted {
+                let scan = platform::scan_fds(p.pid, connected.as_ref());
+                p.open_files = scan.map(|s| s.open);
+                match p.open_files {
+                    Some(n) => self.fd_counts.insert(key, n),
+                    None => self.fd_counts.remove(&key),
+                };
+                if procnet {
+                    sockets.push(scan.map(|s| s.connected));
+                }
+            } else {
+                p.open_files = self.fd_counts.get(&key).copied();
+            }
+        }
+        prune_dead(&mut self.fd_counts, rows);
+        sockets
+    }
+
+    // after a pause, reset the counter baselines so the first rates cover
+    // only the time since resuming rather than the whole pause
+    pub fn rebaseline(&mut self) {
+        self.sys.refresh_all();
+        self.networks.refresh();
+        self.last_ts = Instant::now();
+        let old = std::mem::take(&mut self.last_io);
+        for (pid, proc_) in self.sys.processes() {
+            let io = proc_.disk_usage();
+            let key = (pid.as_u32() as i32, proc_.start_time());
+            self.last_io.insert(
+                key,
+                IoSnapshot {
+                    read: io.total_read_bytes,
+                    write: io.total_written_bytes,
+                    visible: old.get(&key).map_or_else(|| platform::io_visible(key.0), |s| s.visible),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
+
+fn parent_name(sys: &System, process: &sysinfo::Process) -> Option<String> {
+    Some(sys.process(process.parent()?)?.name().to_string())
+}
+
+// totals for the top bar; process state counts are filled in by collect_rows
+pub fn system_stats(sys: &System) -> SystemStats {
+    SystemStats {
+        cpu_percent: sys.global_cpu_info().cpu_usage(),
+        used_mem: sys.used_memory(),
+        total_mem: sys.total_memory().max(1),
+        used_swap: sys.used_swap(),
+        total_swap: sys.total_swap(),
+        states: StateCounts::default(),
+        load_avg: load_average(),
+        uptime_secs: System::uptime(),
+        boot_time: System::boot_time(),
     }
 }
 
-// sums the total network traffic (received, transmitted)
-pub fn total_net_bytes(nets: &Networks) -> (u64, u64) {
-    let mut rx = 0;
-    let mut tx = 0;
-    for (_name, data) in nets.iter() {
-        rx += data.total_received();
-        tx += data.total_transmitted();
+#[cfg(unix)]
+fn load_average() -> Option<[f32; 3]> {
+    let load = System::load_average();
+    Some([load.one as f32, load.five as f32, load.fifteen as f32])
+}
+
+// windows has no load average, sysinfo would only report zeros
+#[cfg(not(unix))]
+fn load_average() -> Option<[f32; 3]> {
+    None
+}
+
+// traced processes are halted too, so they count as stopped
+pub fn proc_state(status: ProcessStatus) -> ProcState {
+    match status {
+        ProcessStatus::Run => ProcState::Running,
+        ProcessStatus::Sleep | ProcessStatus::Idle => ProcState::Sleeping,
+        ProcessStatus::Stop | ProcessStatus::Tracing => ProcState::Stopped,
+        ProcessStatus::Zombie => ProcState::Zombie,
+        _ => ProcState::Other,
     }
-    (rx, tx)
 }
 
-// total bytes read/write from disk
-pub fn total_disk_bytes(sys: &System) -> (u64, u64) {
-    let mut r = 0;
-    let mut w = 0;
-    for (_pid, process) in sys.processes() {
-        let io = process.disk_usage();
-        r += io.total_read_bytes;
-        w += io.total_written_bytes;
+// hottest sensor reading, None when no sensors are exposed
+pub fn max_temperature(components: &Components) -> Option<f32> {
+    components
+        .iter()
+        .map(|c| c.temperature())
+        .filter(|t| t.is_finite())
+        .reduce(f32::max)
+}
+
+// lo on linux, lo0 on macOS, "Loopback Pseudo-Interface" on windows
+pub fn is_loopback(name: &str) -> bool {
+    name == "lo" || name.starts_with("lo0") || name.to_lowercase().contains("loopback")
+}
+
+// per-interface rates from the bytes moved since the previous refresh
+pub fn interface_rates(nets: &Networks, dt_s: f32) -> Vec<InterfaceRate> {
+    let mut rates: Vec<InterfaceRate> = nets
+        .iter()
+        .map(|(name, data)| InterfaceRate {
+            name: name.clone(),
+            rx_bps: data.received() as f32 / dt_s,
+            tx_bps: data.transmitted() as f32 / dt_s,
+        })
+        .collect();
+    rates.sort_by(|a, b| a.name.cmp(&b.name));
+    rates
+}
+
+// one entry per mount point, skipping ones that report no size
+pub fn disk_usage(disks: &Disks) -> Vec<DiskUsage> {
+    let mut out: Vec<DiskUsage> = disks
+        .iter()
+        .filter(|d| d.total_space() > 0)
+        .map(|d| DiskUsage {
+            mount: d.mount_point().display().to_string(),
+            total: d.total_space(),
+            available: d.available_space(),
+        })
+        .collect();
+    out.sort_by(|a, b| a.mount.cmp(&b.mount));
+    out.dedup_by(|a, b| a.mount == b.mount);
+    out
+}
+
+// system disk read/write rate as the sum of per-process rates. Summing
+// lifetime counters instead would shrink whenever a busy process exits and
+// read as a counter reset, flatlining the graph right after activity
+pub fn disk_rates(rows: &[ProcRow]) -> (f32, f32) {
+    rows.iter()
+        .fold((0.0, 0.0), |(r, w), p| {
+            (r + p.read_bps.unwrap_or(0) as f32, w + p.write_bps.unwrap_or(0) as f32)
+        })
+}
+
+// pid and all its descendants, children listed before their parents
+pub fn process_tree(sys: &System, root: i32) -> Vec<i32> {
+    let mut children: HashMap<Pid, Vec<Pid>> = HashMap::new();
+    for (pid, process) in sys.processes() {
+        if let Some(parent) = process.parent() {
+            children.entry(parent).or_default().push(*pid);
+        }
     }
-    (r, w)
-}
\ No newline at end of file
+
+    let mut out = Vec::new();
+    let mut stack = vec![(Pid::from_u32(root as u32), false)];
+    while let Some((pid, expanded)) = stack.pop() {
+        if expanded {
+            out.push(pid.as_u32() as i32);
+            continue;
+        }
+        stack.push((pid, true));
+        if let Some(kids) = children.get(&pid) {
+            stack.extend(kids.iter().map(|k| (*k, false)));
+        }
+    }
+    out
+}
+
+// details for one process, None once it has exited
+pub fn process_details(sys: &System, pid: i32) -> Option<ProcDetails> {
+    let process = sys.process(Pid::from_u32(pid as u32))?;
+    Some(ProcDetails {
+        pid,
+        name: process.name().to_string(),
+        cmd: process.cmd().join(" "),
+        exe: process
+            .exe()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default(),
+        run_time_secs: process.run_time(),
+        parent: process.parent().map(|p| p.as_u32() as i32),
+        parent_name: parent_name(sys, process),
+        threads: process.tasks().map(|t| t.len()),
+        environ: process
+            .environ()
+            .iter()
+            .filter_map(|e| e.split_once('='))
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+    })
+}
@@ -0,0 +1,100 @@
+// This file adds an optional system tray icon (cargo feature "tray")
+
+// without the feature nothing ever sends these
+#[cfg_attr(not(feature = "tray"), allow(dead_code))]
+pub enum TrayCommand {
+    ToggleWindow,
+    Quit,
+}
+
+#[cfg(feature = "tray")]
+mod imp {
+    use super::TrayCommand;
+    use tray_icon::menu::{Menu, MenuEvent, MenuItem};
+    use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
+
+    const SHOW_ID: &str = "toggle";
+    const QUIT_ID: &str = "quit";
+
+    // plain 16x16 square in the title color
+    fn icon() -> Option<Icon> {
+        let rgba = [153u8, 204, 255, 255].repeat(16 * 16);
+        Icon::from_rgba(rgba, 16, 16).ok()
+    }
+
+    fn build() -> Option<TrayIcon> {
+        let menu = Menu::new();
+        menu.append(&MenuItem::with_id(SHOW_ID, "Show / Hide", true, None))
+            .ok()?;
+        menu.append(&MenuItem::with_id(QUIT_ID, "Quit", true, None))
+            .ok()?;
+
+        let mut builder = TrayIconBuilder::new()
+            .with_menu(Box::new(menu))
+            .with_tooltip("ProcDeck");
+        if let Some(icon) = icon() {
+            builder = builder.with_icon(icon);
+        }
+        builder.build().ok()
+    }
+
+    // on linux the tray needs its own thread running a gtk loop; without a
+    // usable gtk/appindicator this reports false and the window stays put
+    #[cfg(target_os = "linux")]
+    pub fn start() -> bool {
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            if gtk::init().is_err() {
+                let _ = tx.send(false);
+                return;
+            }
+            let tray = build();
+            let _ = tx.send(tray.is_some());
+            if tray.is_some() {
+                gtk::main();
+            }
+        });
+        rx.recv().unwrap_or(false)
+    }
+
+    // elsewhere the window event loop on the main thread drives the tray
+    #[cfg(not(target_os = "linux"))]
+    pub fn start() -> bool {
+        thread_local! {
+            static TRAY: std::cell::RefCell<Option<TrayIcon>> = const { std::cell::RefCell::new(None) };
+        }
+        let tray = build();
+        let ok = tray.is_some();
+        TRAY.with(|t| *t.borrow_mut() = tray);
+        ok
+    }
+
+    pub fn poll() -> Option<TrayCommand> {
+        while let Ok(event) = MenuEvent::receiver().try_recv() {
+            match event.id.0.as_str() {
+                SHOW_ID => return Some(TrayCommand::ToggleWindow),
+                QUIT_ID => return Some(TrayCommand::Quit),
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+#[cfg(not(feature = "tray"))]
+mod imp {
+    use super::TrayCommand;
+
+    pub fn start() -> bool {
+        false
+    }
+
+    pub fn poll() -> Option<TrayCommand> {
+        None
+    }
+}
+
+// creates the tray icon, false when there is no tray to minimize to
+pub use imp::start;
+// next pending tray menu command, if any
+pub use imp::poll;
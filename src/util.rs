@@ -3,9 +3,104 @@ pub fn fmt_bytes(bytes: u64) -> String {
     const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
     let mut size = bytes as f64;
     let mut unit = 0;
-    while size > 1024.0 && unit < UNITS.len() - 1 {
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
         size /= 1024.0;
         unit += 1;
     }
     format!("{:.1} {}", size, UNITS[unit])
-}
\ No newline at end of file
+}
+
+// unix seconds as "YYYY-MM-DD HH:MM:SSZ" in UTC
+pub fn fmt_utc(secs: u64) -> String {
+    // days to civil date, from Howard Hinnant's date algorithms
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let rem = secs % 86_400;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+// format a percentage value
+pub fn fmt_percent(v: f32) -> String {
+    format!("{:.1}%", v)
+}
+
+// format a bytes per second rate as bits per second, networking style
+// with decimal prefixes (1 Kbps = 1000 bps)
+pub fn fmt_bits_per_sec(bytes_per_sec: f32) -> String {
+    const UNITS: [&str; 5] = ["bps", "Kbps", "Mbps", "Gbps", "Tbps"];
+    let mut size = bytes_per_sec as f64 * 8.0;
+    let mut unit = 0;
+    while size >= 1000.0 && unit < UNITS.len() - 1 {
+        size /= 1000.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+// format a temperature in celsius
+pub fn fmt_celsius(v: f32) -> String {
+    format!("{:.0}°C", v)
+}
+
+pub fn fmt_load(v: f32) -> String {
+    format!("{:.2}", v)
+}
+
+// format a bytes per second rate
+pub fn fmt_rate(bps: f32) -> String {
+    fmt_bytes(bps as u64) + "/s"
+}
+
+// format a duration in seconds like "2h 14m"
+pub fn fmt_duration(secs: u64) -> String {
+    let (d, h, m, s) = (secs / 86_400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    if d > 0 {
+        format!("{}d {}h", d, h)
+    } else if h > 0 {
+        format!("{}h {}m", h, m)
+    } else if m > 0 {
+        format!("{}m {}s", m, s)
+    } else {
+        format!("{}s", s)
+    }
+}
+
+// byte range of the first case-insensitive match of needle in haystack
+pub fn find_ignore_case(haystack: &str, needle: &str) -> Option<(usize, usize)> {
+    if needle.is_empty() {
+        return None;
+    }
+    let lower = haystack.to_lowercase();
+    // lowercasing can change byte lengths for some non-ascii text
+    if lower.len() != haystack.len() {
+        return None;
+    }
+    let start = lower.find(&needle.to_lowercase())?;
+    let end = start + needle.len();
+    (haystack.is_char_boundary(start) && haystack.is_char_boundary(end)).then_some((start, end))
+}
+
+// "systemd (1)", or "1 (gone)" once the parent has exited
+pub fn fmt_parent(pid: Option<i32>, name: Option<&str>) -> String {
+    match (pid, name) {
+        (Some(pid), Some(name)) => format!("{} ({})", name, pid),
+        (Some(pid), None) => format!("{} (gone)", pid),
+        (None, _) => "-".to_string(),
+    }
+}
@@ -1,100 +1,649 @@
-use iced::{Alignment, Color, Element, Length};
-use iced::widget::{button, checkbox, column, container, row, scrollable, text, text_input, Space};
-use crate::models::{Message, ProcRow, SettingsModel, SortDir, SortKey, Suggestion};
+use iced::{Alignment, Color, Element, Length, Point};
+use iced::widget::{button, checkbox, column, container, pick_list, progress_bar, row, scrollable, text, text_input, Row, Space};
+use crate::models::{ActionRecord, ColumnId, DiskUsage, GraphSeries, HistoryKey, InterfaceRate, Launched, Message, ProcDetails, ProcRow, ProcState, RowLimit, SettingsModel, Severity, SortDir, SortKey, StartRequest, Suggestion, SuggestionCategory, Thresholds, SuggestionAction, SystemGraphs, SystemStats, ThemeChoice, WatchAction, WatchMetric, WatchdogSettings, ALL_INTERFACES, NICE_MAX, NICE_MIN, is_sensitive_env};
+use crate::context_menu::context_area;
+use crate::graphs::{mini_sparkline, trend_arrow};
+use crate::suggestions::DISK_FULL_PERCENT;
 use crate::styles::*;
-use crate::util::fmt_bytes;
+use crate::util::{fmt_bytes, fmt_duration, fmt_parent, fmt_percent, fmt_rate, fmt_utc};
 
 pub fn sortable<'a>(label: &str, key: SortKey, s: &SettingsModel) -> Element<'a, Message> {
-    let mut caption = label.to_string();
-    if s.sort_key == key {
-        caption.push_str(match s.sort_dir {
-            SortDir::Asc => " ↑",
-            SortDir::Desc => " ↓",
-        });
-    }
-    button(text(caption).size(14).shaping(text::Shaping::Advanced))
+    // active column gets a bright arrow, inactive ones a dim hint that they sort
+    let (arrow, arrow_color) = if s.sort_key == key {
+        let arrow = match s.sort_dir {
+            SortDir::Asc => "↑",
+            SortDir::Desc => "↓",
+        };
+        (arrow, Color::from_rgb(1.0, 0.8, 0.3))
+    } else {
+        ("⇅", Color::from_rgba(1.0, 1.0, 1.0, 0.3))
+    };
+
+    let size = density(s.compact).header_text;
+    let caption = row![
+        text(label.to_string()).size(size),
+        tinted(text(arrow).size(size).shaping(text::Shaping::Advanced), arrow_color),
+    ]
+    .spacing(4);
+
+    button(caption)
         .on_press(Message::SortBy(key))
         .width(Length::Fill)
+        .padding(if s.compact { [1, 5] } else { [5, 5] })
         .into()
 }
 
-pub fn controls_row<'a>(settings: &SettingsModel) -> Element<'a, Message> {
+// lets the `/` shortcut focus the filter box
+pub fn filter_input_id() -> text_input::Id {
+    text_input::Id::new("filter")
+}
+
+// an unset minimum shows the placeholder instead of "0"
+fn blank_if_zero(v: u64) -> String {
+    if v == 0 { String::new() } else { v.to_string() }
+}
+
+const ROW_LIMITS: [RowLimit; 5] = [
+    RowLimit::All,
+    RowLimit::Top(25),
+    RowLimit::Top(50),
+    RowLimit::Top(100),
+    RowLimit::Top(200),
+];
+
+// start command plus the collapsible working directory and env fields
+fn start_box<'a>(settings: &SettingsModel) -> Element<'a, Message> {
+    let request = StartRequest::from_settings(settings);
+    let main = row![
+        text_input("Start command…", &settings.cmd_to_start)
+            .on_input(Message::StartChanged)
+            .on_submit(Message::StartNow(request.clone()))
+            .width(Length::Fixed(260.0))
+            .style(iced::theme::TextInput::Custom(Box::new(RoundedTextInput))),
+        Space::with_width(10.0),
+        button("Start")
+            .on_press(Message::StartNow(request.clone()))
+            .padding([6, 24])
+            .style(iced::theme::Button::Custom(Box::new(StartButton))),
+        Space::with_width(10.0),
+        checkbox("Detach", settings.start_detached)
+            .on_toggle(Message::StartDetachedChanged)
+            .text_size(14),
+        button(text(if settings.start_options_open { "▾" } else { "▸" }).shaping(text::Shaping::Advanced))
+            .on_press(Message::ToggleStartOptions)
+            .style(iced::theme::Button::Text),
+    ]
+    .align_items(Alignment::Center);
+    if !settings.start_options_open {
+        return main.into();
+    }
+    let options = row![
+        text_input("Working directory", &settings.start_cwd)
+            .on_input(Message::StartCwdChanged)
+            .on_submit(Message::StartNow(request.clone()))
+            .width(Length::Fixed(200.0))
+            .style(iced::theme::TextInput::Custom(Box::new(RoundedTextInput))),
+        text_input("KEY=value; KEY2=value", &settings.start_env)
+            .on_input(Message::StartEnvChanged)
+            .on_submit(Message::StartNow(request))
+            .width(Length::Fixed(200.0))
+            .style(iced::theme::TextInput::Custom(Box::new(RoundedTextInput))),
+    ]
+    .spacing(10);
+    column![main, options].spacing(6).into()
+}
+
+pub fn controls_row<'a>(
+    settings: &SettingsModel,
+    filter_invalid: bool,
+    paused: bool,
+    graphs_paused: bool,
+    matching: Option<usize>, // rows shown while a filter is active
+) -> Element<'a, Message> {
+    let filter_style: Box<dyn text_input::StyleSheet<Style = iced::Theme>> = if filter_invalid {
+        Box::new(InvalidTextInput)
+    } else {
+        Box::new(RoundedTextInput)
+    };
+    let recent: Element<'a, Message> = if settings.recent_filters.is_empty() {
+        Space::with_width(0).into()
+    } else {
+        pick_list(settings.recent_filters.clone(), None::<String>, Message::ApplyRecentFilter)
+            .placeholder("Recent")
+            .text_size(14)
+            .width(100.0)
+            .into()
+    };
+    let kill_all: Element<'a, Message> = match matching {
+        Some(n) => button(text(format!("Kill all ({})", n)))
+            .on_press_maybe((n > 0).then_some(Message::KillAllMatching))
+            .padding([6, 12])
+            .style(iced::theme::Button::Custom(Box::new(KillButton)))
+            .into(),
+        None => Space::with_width(0).into(),
+    };
+
     row![
         Space::with_width(150.0),
-        text_input("Filter (name or PID)", &settings.filter)
+        text_input("Filter (name, PID or pid:1,2)", &settings.filter)
+            .id(filter_input_id())
             .on_input(Message::FilterChanged)
+            .on_submit(Message::FilterSubmitted)
             .width(360.0)
+            .style(iced::theme::TextInput::Custom(filter_style)),
+        recent,
+        checkbox("Regex", settings.filter_regex)
+            .on_toggle(Message::RegexFilterChanged),
+        checkbox("Group by name", settings.group_by_name)
+            .on_toggle(Message::GroupByNameChanged),
+        checkbox("Mine only", settings.mine_only)
+            .on_toggle(Message::MineOnlyChanged),
+        checkbox("Compact", settings.compact)
+            .on_toggle(Message::CompactChanged),
+        text_input("Min CPU %", &blank_if_zero(settings.min_cpu as u64))
+            .on_input(Message::MinCpuChanged)
+            .width(90.0)
+            .style(iced::theme::TextInput::Custom(Box::new(RoundedTextInput))),
+        text_input("Min MiB", &blank_if_zero(settings.min_mem / (1024 * 1024)))
+            .on_input(Message::MinMemChanged)
+            .width(90.0)
             .style(iced::theme::TextInput::Custom(Box::new(RoundedTextInput))),
+        pick_list(&ROW_LIMITS[..], Some(settings.row_limit), Message::RowLimitChanged)
+            .text_size(14),
+        kill_all,
         Space::with_width(Length::FillPortion(1)),
+        start_box(settings),
+        Space::with_width(Length::FillPortion(1)),
+        button(match settings.theme {
+            ThemeChoice::Dark => "Light",
+            ThemeChoice::Light => "Dark",
+        })
+        .on_press(Message::ToggleTheme)
+        .padding([6, 16])
+        .style(iced::theme::Button::Custom(Box::new(StartButton))),
+        button(if paused { "Resume" } else { "Pause" })
+            .on_press(Message::TogglePause)
+            .padding([6, 16])
+            .style(iced::theme::Button::Custom(Box::new(StartButton))),
+        button(if graphs_paused { "Unfreeze graphs" } else { "Freeze graphs" })
+            .on_press(Message::ToggleGraphsPause)
+            .padding([6, 16])
+            .style(iced::theme::Button::Custom(Box::new(StartButton))),
+        button("Columns")
+            .on_press(Message::ToggleColumnMenu)
+            .padding([6, 16])
+            .style(iced::theme::Button::Custom(Box::new(StartButton))),
+        button("History")
+            .on_press(Message::ToggleHistory)
+            .padding([6, 16])
+            .style(iced::theme::Button::Custom(Box::new(StartButton))),
+        button("Export CSV")
+            .on_press(Message::ExportCsv)
+            .padding([6, 16])
+            .style(iced::theme::Button::Custom(Box::new(StartButton))),
+        button("Export JSON")
+            .on_press(Message::ExportJson)
+            .padding([6, 16])
+            .style(iced::theme::Button::Custom(Box::new(StartButton))),
+        Space::with_width(20.0),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center)
+    .into()
+}
+
+// one line of feedback for actions like export
+pub fn status_line<'a>(status: Option<&str>) -> Element<'a, Message> {
+    match status {
+        Some(msg) => tinted(text(msg.to_string()).size(14), Color::from_rgb(0.7, 0.7, 0.7)).into(),
+        None => Space::with_height(0).into(),
+    }
+}
+
+// louder than the normal confirmation, for init, the display server and such
+pub fn critical_kill_bar<'a>(pid: i32, name: &str, verb: &str) -> Element<'a, Message> {
+    container(
+        row![
+            column![
+                text(format!("⚠ {} (PID {}) is a critical system process", name, pid))
+                    .size(16)
+                    .shaping(text::Shaping::Advanced),
+                text("This may make your system unstable or end your session.").size(14),
+            ]
+            .spacing(2),
+            Space::with_width(Length::Fill),
+            button(text(format!("{} anyway", capitalize(verb))).size(15))
+                .on_press(Message::ConfirmCritical)
+                .style(iced::theme::Button::Custom(Box::new(KillButton)))
+                .padding([4, 10]),
+            button(text("Cancel").size(15))
+                .on_press(Message::CancelCritical)
+                .padding([4, 10]),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    )
+    .padding([8, 12])
+    .width(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(StaticBg {
+        bg: Color::from_rgb(0.45, 0.08, 0.08),
+    })))
+    .into()
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars.next().map_or(String::new(), |c| c.to_uppercase().chain(chars).collect())
+}
+
+// processes started from the start box, newest first
+pub fn launched_panel<'a>(launched: &[Launched]) -> Element<'a, Message> {
+    let entries = launched.iter().rev().map(|l| {
+        let (state, color) = match l.exit {
+            None => ("running".to_string(), Color::from_rgb(0.4, 0.85, 0.4)),
+            Some(status) if status.success() => (status.to_string(), Color::from_rgb(0.7, 0.7, 0.7)),
+            Some(status) => (status.to_string(), Color::from_rgb(1.0, 0.4, 0.4)),
+        };
+        row![
+            text(format!("PID {}", l.child.id())).size(14).width(90.0),
+            text(l.cmd.clone()).size(14).width(Length::Fill),
+            tinted(text(state).size(14), color),
+        ]
+        .spacing(10)
+        .into()
+    });
+    let has_finished = launched.iter().any(|l| l.exit.is_some());
+    column![
+        row![
+            text("Launched processes").size(15),
+            Space::with_width(Length::Fill),
+            button(text("Clear finished").size(13))
+                .on_press_maybe(has_finished.then_some(Message::ClearLaunched))
+                .padding([2, 8]),
+        ]
+        .align_items(Alignment::Center),
+        column(entries).spacing(2),
+    ]
+    .spacing(4)
+    .padding([6, 12])
+    .into()
+}
+
+// header cell for the history panel, same arrows as the process table
+fn history_sortable<'a>(label: &str, key: HistoryKey, active: HistoryKey, dir: SortDir) -> Element<'a, Message> {
+    let (arrow, arrow_color) = if key == active {
+        let arrow = match dir {
+            SortDir::Asc => "↑",
+            SortDir::Desc => "↓",
+        };
+        (arrow, Color::from_rgb(1.0, 0.8, 0.3))
+    } else {
+        ("⇅", Color::from_rgba(1.0, 1.0, 1.0, 0.3))
+    };
+    button(
+        row![
+            text(label.to_string()).size(13),
+            tinted(text(arrow).size(13).shaping(text::Shaping::Advanced), arrow_color),
+        ]
+        .spacing(4),
+    )
+    .on_press(Message::SortHistory(key))
+    .style(iced::theme::Button::Text)
+    .padding([2, 4])
+    .into()
+}
+
+// every kill, suspend, priority change, ... taken this session
+pub fn history_panel<'a>(
+    records: Vec<&ActionRecord>,
+    filter: &str,
+    key: HistoryKey,
+    dir: SortDir,
+) -> Element<'a, Message> {
+    const WIDTHS: [f32; 4] = [170.0, 80.0, 200.0, 130.0];
+    let header = row![
+        container(history_sortable("Time", HistoryKey::Time, key, dir)).width(WIDTHS[0]),
+        container(history_sortable("PID", HistoryKey::Pid, key, dir)).width(WIDTHS[1]),
+        container(history_sortable("Name", HistoryKey::Name, key, dir)).width(WIDTHS[2]),
+        container(history_sortable("Action", HistoryKey::Action, key, dir)).width(WIDTHS[3]),
+        history_sortable("Result", HistoryKey::Result, key, dir),
+    ]
+    .spacing(10);
+    let entries = records.into_iter().map(|r| {
+        let (result, color) = match &r.result {
+            Ok(()) => ("ok".to_string(), Color::from_rgb(0.4, 0.85, 0.4)),
+            Err(e) => (e.clone(), Color::from_rgb(1.0, 0.4, 0.4)),
+        };
+        row![
+            text(fmt_utc(r.at)).size(13).width(WIDTHS[0]),
+            text(r.pid.to_string()).size(13).width(WIDTHS[1]),
+            text(r.name.clone()).size(13).width(WIDTHS[2]),
+            text(r.action.clone()).size(13).width(WIDTHS[3]),
+            tinted(text(result).size(13), color),
+        ]
+        .spacing(10)
+        .padding([0, 4])
+        .into()
+    });
+    column![
         row![
-            text_input("Start command…", &settings.cmd_to_start)
-                .on_input(Message::StartChanged)
-                .on_submit(Message::StartNow)
-                .width(Length::Fixed(260.0))
+            text("Action history").size(15),
+            Space::with_width(Length::Fill),
+            text_input("Search", filter)
+                .on_input(Message::HistoryFilterChanged)
+                .width(220.0)
                 .style(iced::theme::TextInput::Custom(Box::new(RoundedTextInput))),
-            Space::with_width(10.0),
-            button("Start")
-                .on_press(Message::StartNow)
-                .padding([6, 24])
-                .style(iced::theme::Button::Custom(Box::new(StartButton))),
         ]
         .align_items(Alignment::Center),
-        Space::with_width(Length::FillPortion(1)),
+        header,
+        scrollable(column(entries).spacing(2)).height(Length::Fixed(160.0)),
     ]
-    .spacing(10)
-    .align_items(Alignment::Center)
+    .spacing(4)
+    .padding([6, 12])
     .into()
 }
 
-pub fn table_header<'a>(settings: &SettingsModel) -> Element<'a, Message> {
-    #[cfg(target_os = "windows")]
-    let name_width = 450;
-    #[cfg(all(target_family = "unix", not(target_os = "macos")))]
-    let name_width = 510;
-    #[cfg(target_os = "macos")]
-    let name_width = Length::FillPortion(3);
+// asks before the Delete shortcut kills anything
+pub fn confirm_kill_bar<'a>(pid: i32, name: &str) -> Element<'a, Message> {
+    container(
+        row![
+            text(format!("Kill {} (PID {})?", name, pid)).size(15),
+            Space::with_width(Length::Fill),
+            button(text("Kill").size(15))
+                .on_press(Message::ConfirmKill)
+                .style(iced::theme::Button::Custom(Box::new(KillButton)))
+                .padding([4, 10]),
+            button(text("Cancel").size(15))
+                .on_press(Message::CancelKill)
+                .padding([4, 10]),
+            tinted(text("Enter / Esc").size(13), Color::from_rgb(0.7, 0.7, 0.7)),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    )
+    .padding([6, 12])
+    .width(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(StaticBg {
+        bg: Color::from_rgb(0.35, 0.27, 0.08),
+    })))
+    .into()
+}
 
+pub fn confirm_kill_all_bar<'a>(count: usize) -> Element<'a, Message> {
     container(
         row![
-            container(sortable("PID", SortKey::Pid, settings)).width(70.0),
-            container(sortable("Name", SortKey::Name, settings)).width(name_width),
-            container(sortable("CPU %", SortKey::Cpu, settings)).width(80.0),
-            container(sortable("Memory", SortKey::Mem, settings)).width(110.0),
-            container(sortable("Read/s", SortKey::Read, settings)).width(110.0),
-            container(sortable("Write/s", SortKey::Write, settings)).width(110.0),
-            container(text("Actions").size(18))
-                .width(Length::FillPortion(2))
-                .center_x()
-                .center_y(),
-        ]
-        .spacing(20)
-        .align_items(Alignment::Center)
+            text(format!("Kill all {} processes matching the filter?", count)).size(15),
+            Space::with_width(Length::Fill),
+            button(text("Kill all").size(15))
+                .on_press(Message::ConfirmKillAll)
+                .style(iced::theme::Button::Custom(Box::new(KillButton)))
+                .padding([4, 10]),
+            button(text("Cancel").size(15))
+                .on_press(Message::CancelKillAll)
+                .padding([4, 10]),
+            tinted(text("Esc").size(13), Color::from_rgb(0.7, 0.7, 0.7)),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    )
+    .padding([6, 12])
+    .width(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(StaticBg {
+        bg: Color::from_rgb(0.35, 0.27, 0.08),
+    })))
+    .into()
+}
+
+// actions for every checked row at once
+pub fn batch_bar<'a>(count: usize) -> Element<'a, Message> {
+    container(
+        row![
+            text(format!("{} selected", count)).size(15),
+            Space::with_width(Length::Fill),
+            button(text("Kill").size(15))
+                .on_press(Message::BatchKill)
+                .style(iced::theme::Button::Custom(Box::new(KillButton)))
+                .padding([4, 10]),
+            button(text("Suspend").size(15))
+                .on_press(Message::BatchSuspend)
+                .style(iced::theme::Button::Custom(Box::new(SuspendButton)))
+                .padding([4, 10]),
+            button(text("Resume").size(15))
+                .on_press(Message::BatchResume)
+                .style(iced::theme::Button::Custom(Box::new(ResumeButton)))
+                .padding([4, 10]),
+            button(text("Clear").size(15))
+                .on_press(Message::ClearChecked)
+                .padding([4, 10]),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    )
+    .padding([6, 12])
+    .width(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(StaticBg {
+        bg: Color::from_rgb(0.15, 0.22, 0.32),
+    })))
+    .into()
+}
+
+// transient banner for failed actions
+pub fn error_toast<'a>(error: Option<&str>) -> Element<'a, Message> {
+    match error {
+        Some(msg) => container(text(msg.to_string()).size(15))
+        .padding([8, 12])
+        .width(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(StaticBg {
+            bg: Color::from_rgb(0.45, 0.12, 0.12),
+        })))
+        .into(),
+        None => Space::with_height(0).into(),
+    }
+}
+
+// column geometry shared by the header and every row kind, so they can't
+// drift apart; the name column takes whatever the others leave
+const NAME_WIDTH: Length = Length::Fill;
+// Kill, Suspend/Resume and the ⋯ overflow button
+const ACTIONS_WIDTH: Length = Length::Fixed(200.0);
+// leading multi-select checkbox after the new-process stripe, the header
+// leaves the same gap
+const CHECK_WIDTH: Length = Length::Fixed(25.0);
+const COLUMN_SPACING: u16 = 20;
+const ROW_PADDING_X: u16 = 10;
+
+// sizes that change with the table density
+#[derive(Debug, Clone, Copy)]
+struct Density {
+    row_pad: u16, // above and below each row
+    header_pad: u16,
+    text: u16,
+    header_text: u16,
+    button_text: u16,
+    button_pad: [u16; 2],
+}
+
+const NORMAL: Density = Density {
+    row_pad: 4,
+    header_pad: 12,
+    text: 16,
+    header_text: 14,
+    button_text: 15,
+    button_pad: [4, 10],
+};
+// roughly half the height, about twice the rows on screen
+const COMPACT: Density = Density {
+    row_pad: 1,
+    header_pad: 4,
+    text: 13,
+    header_text: 12,
+    button_text: 12,
+    button_pad: [1, 6],
+};
+
+fn density(compact: bool) -> Density {
+    if compact {
+        COMPACT
+    } else {
+        NORMAL
+    }
+}
+
+fn column_width(id: ColumnId) -> Length {
+    match id {
+        ColumnId::Pid | ColumnId::Priority | ColumnId::Threads | ColumnId::OpenFiles | ColumnId::MemPercent => {
+            Length::Fixed(70.0)
+        }
+        ColumnId::Name => NAME_WIDTH,
+        ColumnId::Cpu => Length::Fixed(130.0),
+        ColumnId::Uptime => Length::Fixed(90.0),
+        ColumnId::User
+        | ColumnId::Mem
+        | ColumnId::Swap
+        | ColumnId::Read
+        | ColumnId::Write
+        | ColumnId::ReadTotal
+        | ColumnId::WriteTotal
+        | ColumnId::NetRx
+        | ColumnId::NetTx => Length::Fixed(110.0),
+    }
+}
+
+pub fn table_header<'a>(settings: &SettingsModel) -> Element<'a, Message> {
+    let mut cells: Vec<Element<'a, Message>> = vec![Space::with_width(CHECK_WIDTH).into()];
+    cells.extend(settings.columns.iter().map(|&id| {
+        container(sortable(id.label(), id.sort_key(), settings))
+            .width(column_width(id))
+            .into()
+    }));
+    let d = density(settings.compact);
+    cells.push(
+        container(text("Actions").size(d.header_text + 4))
+            .width(ACTIONS_WIDTH)
+            .center_x()
+            .center_y()
+            .into(),
+    );
+
+    container(
+        Row::with_children(cells)
+            .spacing(COLUMN_SPACING)
+            .align_items(Alignment::Center)
     )
-    .padding([12, 10])
+    .padding([d.header_pad, ROW_PADDING_X])
     .into()
 }
 
-pub fn top_bar<'a>(proc_count: usize, dot_phase: usize) -> Element<'a, Message> {
+// choose which columns show and in what order
+pub fn column_menu<'a>(columns: &[ColumnId]) -> Element<'a, Message> {
+    let hidden = ColumnId::ALL.into_iter().filter(|id| !columns.contains(id));
+    let entries = columns.iter().copied().chain(hidden).map(|id| {
+        let shown = columns.contains(&id);
+        let mut entry = row![checkbox(id.label(), shown)
+            .on_toggle(move |v| Message::ColumnVisible(id, v))]
+        .spacing(2)
+        .align_items(Alignment::Center);
+        if shown {
+            entry = entry
+                .push(
+                    button(text("◀").size(12).shaping(text::Shaping::Advanced))
+                        .on_press(Message::MoveColumn(id, -1))
+                        .style(iced::theme::Button::Text)
+                        .padding([0, 4]),
+                )
+                .push(
+                    button(text("▶").size(12).shaping(text::Shaping::Advanced))
+                        .on_press(Message::MoveColumn(id, 1))
+                        .style(iced::theme::Button::Text)
+                        .padding([0, 4]),
+                );
+        }
+        entry.into()
+    });
+
+    container(Row::with_children(entries).spacing(16).align_items(Alignment::Center))
+        .padding([6, 10])
+        .width(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(StaticBg { bg: Color::from_rgb(0.18, 0.18, 0.22) })))
+        .into()
+}
+
+pub fn top_bar<'a>(
+    dot_phase: usize,
+    stats: &SystemStats,
+    paused: bool,
+    sampling: Option<(f32, bool)>, // observed refresh interval, and whether it lags
+) -> Element<'a, Message> {
     let dots = ".".repeat(dot_phase);
-    let status_text = format!("{} Processes currently running", proc_count);
+    let states = &stats.states;
+    let mut status_text = format!("{} total", states.total);
+    for (count, label) in [
+        (states.running, "running"),
+        (states.sleeping, "sleeping"),
+        (states.stopped, "stopped"),
+        (states.zombie, "zombie"),
+    ] {
+        if count > 0 {
+            status_text += &format!(" · {} {}", count, label);
+        }
+    }
+    let mut stats_text = format!(
+        "CPU {:.1}%   Mem {} / {}   Swap {} / {}",
+        stats.cpu_percent,
+        fmt_bytes(stats.used_mem),
+        fmt_bytes(stats.total_mem),
+        fmt_bytes(stats.used_swap),
+        fmt_bytes(stats.total_swap)
+    );
+    if let Some([one, five, fifteen]) = stats.load_avg {
+        stats_text += &format!("   Load {:.2} {:.2} {:.2}", one, five, fifteen);
+    }
+    stats_text += &format!("   up {}", fmt_duration(stats.uptime_secs));
 
     let dot_display = text(format!("{:<3}", dots))
         .size(16)
-        .style(Color::from_rgb(1.0, 1.0, 0.0))
         .font(iced::Font::MONOSPACE);
 
+    // rates are per second of the observed interval, so say when it drifts
+    let sample_rate: Element<'a, Message> = match sampling {
+        Some((secs, lagging)) if !paused => {
+            let rate = format!("{:.1} samples/s", 1.0 / secs);
+            if lagging {
+                tinted(text(format!("{}, lagging", rate)).size(14), Color::from_rgb(1.0, 0.6, 0.2)).into()
+            } else {
+                tinted(text(rate).size(14), Color::from_rgb(0.6, 0.6, 0.6)).into()
+            }
+        }
+        _ => Space::with_width(0).into(),
+    };
+
+    let paused_badge: Element<'a, Message> = if paused {
+        container(text("PAUSED").size(14))
+            .padding([2, 8])
+            .style(iced::theme::Container::Custom(Box::new(StaticBg {
+                bg: Color::from_rgb(0.7, 0.45, 0.1),
+            })))
+            .into()
+    } else {
+        Space::with_width(0).into()
+    };
+
     row![
-        text("ProcDeck – Process Monitor & Manager")
-            .size(23)
-            .style(Color::from_rgb(0.6, 0.8, 1.0)),
+        tinted(
+            text("ProcDeck – Process Monitor & Manager").size(23),
+            Color::from_rgb(0.6, 0.8, 1.0),
+        ),
+        Space::with_width(16.0),
+        paused_badge,
         Space::with_width(Length::Fill),
-        row![
-            text(status_text)
-                .size(16)
-                .style(Color::from_rgb(1.0, 1.0, 0.0)),
-            dot_display,
-        ]
-        .spacing(2)
+        tinted(text(stats_text).size(16), Color::from_rgb(0.8, 0.8, 0.8)),
+        Space::with_width(30.0),
+        sample_rate,
+        Space::with_width(30.0),
+        tinted(
+            row![text(status_text).size(16), dot_display].spacing(2),
+            Color::from_rgb(1.0, 1.0, 0.0),
+        ),
     ]
     .align_items(Alignment::Center)
     .padding([8, 30])
@@ -102,56 +651,586 @@ pub fn top_bar<'a>(proc_count: usize, dot_phase: usize) -> Element<'a, Message>
 }
 
 
-pub fn process_row<'a>(p: &ProcRow) -> Element<'a, Message> {
-    #[cfg(target_os = "windows")]
-    let name_width = 450;
-    #[cfg(all(target_family = "unix", not(target_os = "macos")))]
-    let name_width = 510;
-    #[cfg(target_os = "macos")]
-    let name_width = Length::FillPortion(3);
+// a capacity bar per mount, red once it crosses the suggestion threshold
+pub fn disk_panel<'a>(disks: &[DiskUsage]) -> Element<'a, Message> {
+    let items = disks.iter().map(|d| {
+        let pct = d.used_percent();
+        let color = if pct >= DISK_FULL_PERCENT { Color::from_rgb(1.0, 0.4, 0.4) } else { Color::from_rgb(0.3, 0.8, 1.0) };
+        column![
+            row![
+                text(d.mount.clone()).size(13),
+                Space::with_width(Length::Fill),
+                tinted(text(format!("{} / {}", fmt_bytes(d.used()), fmt_bytes(d.total))).size(13), color),
+            ],
+            progress_bar(0.0..=100.0, pct).height(6.0),
+        ]
+        .spacing(2)
+        .width(220.0)
+        .into()
+    });
+    scrollable(Row::with_children(items).spacing(16))
+        .direction(scrollable::Direction::Horizontal(scrollable::Properties::default()))
+        .into()
+}
+
+// one line of latest values with the direction each is heading, readable
+// even when the graphs are squeezed out of the window
+pub fn summary_strip<'a>(graphs: &SystemGraphs, net_fmt: fn(f32) -> String) -> Element<'a, Message> {
+    let item = |label: &str, series: &GraphSeries, fmt: fn(f32) -> String| {
+        let value = series.points.back().map_or("—".to_string(), |v| fmt(*v));
+        format!("{} {} {}", label, value, trend_arrow(&series.points))
+    };
+    let parts = [
+        item("CPU", &graphs.cpu, fmt_percent),
+        item("Mem", &graphs.mem, fmt_percent),
+        item("Disk R", &graphs.disk_read, fmt_rate),
+        item("Disk W", &graphs.disk_write, fmt_rate),
+        item("Net RX", &graphs.net_rx, net_fmt),
+        item("Net TX", &graphs.net_tx, net_fmt),
+    ];
+    tinted(
+        text(parts.join("    ")).size(14).shaping(text::Shaping::Advanced),
+        Color::from_rgb(0.7, 0.7, 0.7),
+    )
+    .padding([0, 30])
+    .into()
+}
+
+// process name with the filter match drawn in a highlight color
+pub fn highlighted_name<'a>(name: &str, highlight: Option<(usize, usize)>, size: u16) -> Element<'a, Message> {
+    match highlight {
+        Some((start, end)) => row![
+            text(&name[..start]).size(size),
+            tinted(text(&name[start..end]).size(size), Color::from_rgb(1.0, 0.85, 0.2)),
+            text(&name[end..]).size(size),
+        ]
+        .into(),
+        None => text(name.to_string()).size(size).into(),
+    }
+}
+
+// what any column shows for a value that couldn't be read
+pub const UNKNOWN: &str = "—";
+
+// plain text of a cell, shared with the headless table
+pub fn cell_text(id: ColumnId, p: &ProcRow) -> String {
+    let known = |v: Option<String>| v.unwrap_or_else(|| UNKNOWN.to_string());
+    let rate = |b: Option<u64>| known(b.map(|b| fmt_bytes(b) + "/s"));
+    match id {
+        ColumnId::Pid => p.pid.to_string(),
+        ColumnId::Name => p.name.clone(),
+        ColumnId::User => p.user.clone(),
+        ColumnId::Priority => known(p.priority.map(|n| n.to_string())),
+        ColumnId::Cpu => format!("{:.1}", p.cpu),
+        ColumnId::Mem => fmt_bytes(p.mem_bytes),
+        ColumnId::MemPercent => format!("{:.1}%", p.mem_percent),
+        ColumnId::Swap => known(p.swap_bytes.map(fmt_bytes)),
+        ColumnId::Read => rate(p.read_bps),
+        ColumnId::Write => rate(p.write_bps),
+        ColumnId::Uptime => fmt_duration(p.uptime_secs),
+        ColumnId::ReadTotal => known(p.read_total.map(fmt_bytes)),
+        ColumnId::WriteTotal => known(p.write_total.map(fmt_bytes)),
+        ColumnId::Threads => known((p.threads > 0).then(|| p.threads.to_string())),
+        ColumnId::OpenFiles => known(p.open_files.map(|n| n.to_string())),
+        ColumnId::NetRx => rate(p.net_rx_bps),
+        ColumnId::NetTx => rate(p.net_tx_bps),
+    }
+}
+
+// the basics always go first so a pasted row means something on its own
+const COPY_COLUMNS: [ColumnId; 6] =
+    [ColumnId::Pid, ColumnId::Name, ColumnId::Cpu, ColumnId::Mem, ColumnId::Read, ColumnId::Write];
+
+// one row as tab separated "Label: value" pairs, the basics then whatever
+// else is on screen
+pub fn row_text(p: &ProcRow, columns: &[ColumnId]) -> String {
+    let extra = columns.iter().copied().filter(|id| !COPY_COLUMNS.contains(id));
+    COPY_COLUMNS
+        .into_iter()
+        .chain(extra)
+        .map(|id| format!("{}: {}", id.label(), cell_text(id, p)))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+// a single process holding this share of RAM is as hot as it gets; the
+// memory alert threshold is about the whole machine so it doesn't fit here
+const MEM_HEAT_PERCENT: f32 = 25.0;
+
+// one table cell for a column
+fn process_cell<'a>(
+    id: ColumnId,
+    p: &ProcRow,
+    cpu_history: Option<&'a GraphSeries>,
+    highlight: Option<(usize, usize)>,
+    thresholds: &Thresholds,
+    size: u16,
+) -> Element<'a, Message> {
+    let cell: Element<'a, Message> = match id {
+        ColumnId::Name => highlighted_name(&p.name, highlight, size),
+        ColumnId::Cpu => {
+            // red once a process alone reaches the cpu alert threshold
+            let heat = heat_color(p.cpu / thresholds.cpu_percent.max(1) as f32);
+            let value = tinted(text(cell_text(id, p)).size(size), heat).width(45);
+            match cpu_history {
+                Some(h) => row![value, mini_sparkline(h, Color::from_rgb(1.0, 0.3, 0.3))]
+                    .align_items(Alignment::Center)
+                    .into(),
+                None => value.into(),
+            }
+        }
+        ColumnId::Mem | ColumnId::MemPercent => {
+            tinted(text(cell_text(id, p)).size(size), heat_color(p.mem_percent / MEM_HEAT_PERCENT)).into()
+        }
+        _ => text(cell_text(id, p)).size(size).into(),
+    };
+    container(cell).width(column_width(id)).into()
+}
+
+// per-row UI state the table hands down
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RowFlags {
+    pub selected: bool,
+    pub checked: bool,
+    pub actions_open: bool, // the ⋯ menu is showing
+    pub compact: bool,
+    pub new_fade: f32, // 1 for a process that just spawned, 0 once it has faded
+}
+
+pub fn process_row<'a>(
+    p: &ProcRow,
+    columns: &[ColumnId],
+    flags: RowFlags,
+    cpu_history: Option<&'a GraphSeries>,
+    highlight: Option<(usize, usize)>,
+    thresholds: &Thresholds,
+) -> Element<'a, Message> {
+    let d = density(flags.compact);
+    // only the action that applies to the current state; zombies can't be
+    // signalled so the button stays disabled for them
+    let suspend_resume = match p.status {
+        ProcState::Stopped => button(text("Resume").size(d.button_text))
+            .on_press(Message::Resume(p.pid))
+            .style(iced::theme::Button::Custom(Box::new(ResumeButton))),
+        ProcState::Zombie => button(text("Suspend").size(d.button_text))
+            .style(iced::theme::Button::Custom(Box::new(SuspendButton))),
+        _ => button(text("Suspend").size(d.button_text))
+            .on_press(Message::Suspend(p.pid))
+            .style(iced::theme::Button::Custom(Box::new(SuspendButton))),
+    };
+
+    let pid = p.pid;
+    // everything else lives in the row menu, opened here or by right click
+    let more = button(text("⋯").size(d.button_text).shaping(text::Shaping::Advanced))
+        .on_press(Message::ShowActionsMenu(pid))
+        .padding(d.button_pad);
+    let below = if flags.compact { 18.0 } else { 30.0 };
+    let more_menu = flags.actions_open.then(|| (Point::new(0.0, below), process_menu(p)));
+    let more = context_area(more, more_menu, move |_| Message::ShowActionsMenu(pid), Message::CloseContextMenu);
+
+    // green bar on the left edge while the process is new, kept as a blank
+    // of the same size afterwards so the columns don't shift
+    let stripe = container(Space::new(3.0, d.text as f32 + 4.0));
+    let stripe = if flags.new_fade > 0.0 {
+        stripe.style(iced::theme::Container::Custom(Box::new(StaticBg {
+            bg: Color { a: flags.new_fade, ..Color::from_rgb(0.2, 0.85, 0.3) },
+        })))
+    } else {
+        stripe
+    };
+    let check = checkbox("", flags.checked).on_toggle(move |v| Message::CheckProcess(pid, v)).spacing(0);
+    let mut cells: Vec<Element<'a, Message>> =
+        vec![row![stripe, check].spacing(2).align_items(Alignment::Center).width(CHECK_WIDTH).into()];
+    cells.extend(columns.iter().map(|&id| process_cell(id, p, cpu_history, highlight, thresholds, d.text)));
+    cells.push(
+        container(
+            row![
+                button(text("Kill").size(d.button_text))
+                    .on_press(Message::Kill(p.pid))
+                    .style(iced::theme::Button::Custom(Box::new(KillButton)))
+                    .padding(d.button_pad),
+                suspend_resume.padding(d.button_pad),
+                more,
+            ]
+            .spacing(6)
+        )
+        .padding([0, 8, 0, 0])
+        .width(ACTIONS_WIDTH)
+        .into(),
+    );
+
+    container(Row::with_children(cells).spacing(COLUMN_SPACING).align_items(Alignment::Center))
+        .padding([d.row_pad, ROW_PADDING_X])
+        .style(row_style(p.status, flags.selected))
+        .into()
+}
+
+// summary row of a name group, clicking it expands the group
+pub fn group_row<'a>(
+    summary: &ProcRow,
+    count: usize,
+    expanded: bool,
+    columns: &[ColumnId],
+    highlight: Option<(usize, usize)>,
+    thresholds: &Thresholds,
+    compact: bool,
+) -> Element<'a, Message> {
+    let d = density(compact);
+    let mut cells: Vec<Element<'a, Message>> = vec![Space::with_width(CHECK_WIDTH).into()];
+    cells.extend(columns.iter().map(|&id| match id {
+            // the pid of one member would be misleading here
+            ColumnId::Pid => container(
+                text(if expanded { "▾" } else { "▸" }).size(d.text).shaping(text::Shaping::Advanced),
+            )
+            .width(column_width(id))
+            .into(),
+            _ => process_cell(id, summary, None, highlight, thresholds, d.text),
+        }));
+    cells.push(
+        container(
+            container(text(format!("{} processes", count)).size(d.button_text))
+                .padding([2, 8])
+                .style(iced::theme::Container::Custom(Box::new(StaticBg {
+                    bg: Color::from_rgb(0.25, 0.3, 0.4),
+                }))),
+        )
+        .width(ACTIONS_WIDTH)
+        .into(),
+    );
+
+    container(Row::with_children(cells).spacing(COLUMN_SPACING).align_items(Alignment::Center))
+        .padding([d.row_pad, ROW_PADDING_X])
+        .into()
+}
+
+#[cfg(target_family = "unix")]
+fn signal_item<'a>(pid: i32) -> Element<'a, Message> {
+    row![text("Signal").size(14), signal_picker(pid)]
+        .spacing(8)
+        .padding([2, 12])
+        .align_items(Alignment::Center)
+        .into()
+}
 
+#[cfg(target_family = "windows")]
+fn signal_item<'a>(_pid: i32) -> Element<'a, Message> {
+    Space::with_height(0).into()
+}
+
+// core pinning is only offered where sched_setaffinity exists
+#[cfg(target_os = "linux")]
+fn affinity_item<'a>(pid: i32) -> Element<'a, Message> {
+    button(text("CPU affinity…").size(14))
+        .on_press(Message::EditAffinity(pid))
+        .width(Length::Fill)
+        .padding([4, 12])
+        .style(iced::theme::Button::Text)
+        .into()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn affinity_item<'a>(_pid: i32) -> Element<'a, Message> {
+    Space::with_height(0).into()
+}
+
+// one checkbox per core, pre-filled with the current mask
+#[cfg(target_os = "linux")]
+pub fn affinity_bar<'a>(pid: i32, name: &str, cores: &[bool]) -> Element<'a, Message> {
+    let boxes = cores.iter().enumerate().map(|(core, &on)| {
+        checkbox(core.to_string(), on)
+            .on_toggle(move |v| Message::AffinityCore(core, v))
+            .text_size(14)
+            .into()
+    });
+    let chosen: Vec<usize> = cores.iter().enumerate().filter(|(_, on)| **on).map(|(c, _)| c).collect();
+    let apply = button(text("Apply").size(15)).padding([4, 10]);
+    // the kernel refuses an empty mask, so don't offer it
+    let apply = if chosen.is_empty() { apply } else { apply.on_press(Message::SetAffinity(pid, chosen)) };
     container(
         row![
-            text(p.pid).width(70.0),
-            text(p.name.clone()).width(name_width),
-            text(format!("{:.1}", p.cpu)).width(80.0),
-            text(fmt_bytes(p.mem_bytes)).width(110.0),
-            text(fmt_bytes(p.read_bps) + "/s").width(110.0),
-            text(fmt_bytes(p.write_bps) + "/s").width(110.0),
-            container(
-                row![
-                    button(text("Kill").size(15))
-                        .on_press(Message::Kill(p.pid))
-                        .style(iced::theme::Button::Custom(Box::new(KillButton)))
-                        .padding([4, 10]),
-                    button(text("Suspend").size(15))
-                        .on_press(Message::Suspend(p.pid))
-                        .style(iced::theme::Button::Custom(Box::new(SuspendButton)))
-                        .padding([4, 10]),
-                    button(text("Resume").size(15))
-                        .on_press(Message::Resume(p.pid))
-                        .style(iced::theme::Button::Custom(Box::new(ResumeButton)))
-                        .padding([4, 10]),
-                    button(text("Boost").size(15))
-                        .on_press(Message::Boost(p.pid))
-                        .style(iced::theme::Button::Custom(Box::new(BoostButton)))
-                        .padding([4, 10]),
-                    button(text("Lower").size(15))
-                        .on_press(Message::Lower(p.pid))
-                        .style(iced::theme::Button::Custom(Box::new(LowerButton)))
-                        .padding([4, 10]),
-                ]
-                .spacing(6)
-            )
-            .padding([0, 8, 0, 0])
-            .width(Length::FillPortion(2))
+            text(format!("Cores for {} (PID {}):", name, pid)).size(15),
+            Row::with_children(boxes).spacing(8).align_items(Alignment::Center),
+            Space::with_width(Length::Fill),
+            apply,
+            button(text("Cancel").size(15))
+                .on_press(Message::CloseAffinity)
+                .padding([4, 10]),
         ]
-        .spacing(20),
+        .spacing(10)
+        .align_items(Alignment::Center),
     )
-    .padding([4, 10])
+    .padding([6, 12])
+    .width(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(StaticBg {
+        bg: Color::from_rgb(0.15, 0.22, 0.32),
+    })))
     .into()
 }
 
+// right-click menu for a process row
+pub fn process_menu<'a>(p: &ProcRow) -> Element<'a, Message> {
+    let item = |label: &str, msg: Message| {
+        button(text(label.to_string()).size(14))
+            .on_press(msg)
+            .width(Length::Fill)
+            .padding([4, 12])
+            .style(iced::theme::Button::Text)
+    };
+    let suspend_resume = if p.status == ProcState::Stopped {
+        item("Resume", Message::Resume(p.pid))
+    } else {
+        item("Suspend", Message::Suspend(p.pid))
+    };
+    // kernel threads have no command line to relaunch
+    let restart = item("Restart", Message::Restart(p.pid))
+        .on_press_maybe(p.restartable.then_some(Message::Restart(p.pid)));
+
+    container(
+        column![
+            tinted(text(format!("{} ({})", p.name, p.pid)).size(13), Color::from_rgb(0.6, 0.6, 0.6))
+                .padding([2, 12]),
+            item("Kill", Message::Kill(p.pid)),
+            item("Kill tree", Message::KillTree(p.pid)),
+            item("Terminate", Message::Terminate(p.pid)),
+            restart,
+            suspend_resume,
+            item("Boost", Message::Boost(p.pid)),
+            item("Lower", Message::Lower(p.pid)),
+            row![text("Priority").size(14), priority_picker(p.pid, p.priority)]
+                .spacing(8)
+                .padding([2, 12])
+                .align_items(Alignment::Center),
+            signal_item(p.pid),
+            affinity_item(p.pid),
+            item("Copy PID", Message::CopyPid(p.pid)),
+            item("Copy name", Message::CopyName(p.name.clone())),
+            item("Copy row", Message::CopyRow(p.pid)),
+        ]
+        .spacing(2),
+    )
+    .width(200.0)
+    .padding(4)
+    .style(iced::theme::Container::Custom(Box::new(StaticBg { bg: Color::from_rgb(0.2, 0.2, 0.24) })))
+    .into()
+}
+
+// variables whose name or value contains filter, credential-looking values
+// masked unless revealed. A masked value is never searched, or guessing in
+// the filter box would give it away a character at a time
+pub fn env_entries<'a>(environ: &'a [(String, String)], filter: &str, reveal: bool) -> Vec<(&'a str, String)> {
+    let filter = filter.trim().to_lowercase();
+    environ
+        .iter()
+        .map(|(k, v)| (k, v, !reveal && is_sensitive_env(k)))
+        .filter(|(k, v, masked)| {
+            filter.is_empty()
+                || k.to_lowercase().contains(&filter)
+                || (!masked && v.to_lowercase().contains(&filter))
+        })
+        .map(|(k, v, masked)| (k.as_str(), if masked { "••••••".to_string() } else { v.clone() }))
+        .collect()
+}
+
+fn environ_view<'a>(environ: &[(String, String)], filter: &str, reveal: bool) -> Element<'a, Message> {
+    let entries: Vec<Element<'a, Message>> = env_entries(environ, filter, reveal)
+        .into_iter()
+        .map(|(k, v)| {
+            row![
+                tinted(text(k.to_string()).size(13), Color::from_rgb(0.6, 0.8, 1.0)),
+                text(v).size(13).shaping(text::Shaping::Advanced),
+            ]
+            .spacing(8)
+            .into()
+        })
+        .collect();
+    let body: Element<'a, Message> = if environ.is_empty() {
+        // other users' processes and kernel threads don't expose it
+        tinted(text("Not readable for this process").size(13), Color::from_rgb(0.6, 0.6, 0.6)).into()
+    } else if entries.is_empty() {
+        tinted(text("No matching variables").size(13), Color::from_rgb(0.6, 0.6, 0.6)).into()
+    } else {
+        scrollable(column(entries).spacing(2)).height(Length::Fixed(140.0)).into()
+    };
+    column![
+        row![
+            tinted(text("Environment").size(14), Color::from_rgb(0.6, 0.6, 0.6)),
+            Space::with_width(Length::Fill),
+            checkbox("Reveal secrets", reveal)
+                .on_toggle(|_| Message::ToggleEnvReveal)
+                .text_size(13),
+            text_input("Search", filter)
+                .on_input(Message::EnvFilterChanged)
+                .width(220.0)
+                .style(iced::theme::TextInput::Custom(Box::new(RoundedTextInput))),
+        ]
+        .spacing(12)
+        .align_items(Alignment::Center),
+        body,
+    ]
+    .spacing(4)
+    .into()
+}
+
+// details pane for the selected process
+pub fn details_panel<'a>(d: &ProcDetails, env_filter: &str, env_revealed: bool) -> Element<'a, Message> {
+    let field = |label: &str, value: String| {
+        row![
+            tinted(text(label.to_string()).size(14), Color::from_rgb(0.6, 0.6, 0.6)).width(110.0),
+            text(value).size(14),
+        ]
+        .spacing(8)
+    };
+
+    let or_dash = |s: &str| if s.is_empty() { "-".to_string() } else { s.to_string() };
+
+    container(
+        column![
+            row![
+                tinted(
+                    text(format!("{} (PID {})", d.name, d.pid)).size(16),
+                    Color::from_rgb(0.6, 0.8, 1.0),
+                ),
+                Space::with_width(Length::Fill),
+                button(text("Copy PID").size(13))
+                    .on_press(Message::CopyPid(d.pid))
+                    .style(iced::theme::Button::Text)
+                    .padding([0, 8]),
+                button(text("Copy name").size(13))
+                    .on_press(Message::CopyName(d.name.clone()))
+                    .style(iced::theme::Button::Text)
+                    .padding([0, 8]),
+                button(text("×").size(16).shaping(text::Shaping::Advanced))
+                    .on_press(Message::SelectProcess(d.pid))
+                    .style(iced::theme::Button::Text)
+                    .padding([0, 8]),
+            ]
+            .align_items(Alignment::Center),
+            field("Command", or_dash(&d.cmd)),
+            field("Executable", or_dash(&d.exe)),
+            field("Running for", fmt_duration(d.run_time_secs)),
+            field("Child of", fmt_parent(d.parent, d.parent_name.as_deref())),
+            field("Threads", d.threads.map_or("-".to_string(), |t| t.to_string())),
+            environ_view(&d.environ, env_filter, env_revealed),
+        ]
+        .spacing(4),
+    )
+    .padding([8, 12])
+    .width(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(StaticBg { bg: Color::from_rgb(0.18, 0.18, 0.22) })))
+    .into()
+}
+
+// lets the user jump straight to a nice value instead of stepping
+pub fn nice_picker<'a>(pid: i32, current: Option<i32>) -> Element<'a, Message> {
+    let levels: Vec<i32> = (NICE_MIN..=NICE_MAX).collect();
+    pick_list(levels, current, move |nice| Message::SetPriority(pid, nice))
+        .placeholder("Nice")
+        .text_size(14)
+        .padding([4, 8])
+        .width(70.0)
+        .into()
+}
+
+#[cfg(target_family = "unix")]
+fn priority_picker<'a>(pid: i32, current: Option<i32>) -> Element<'a, Message> {
+    nice_picker(pid, current)
+}
+
+// windows has six classes rather than a nice scale, so pick one of those
+#[cfg(target_family = "windows")]
+fn priority_picker<'a>(pid: i32, current: Option<i32>) -> Element<'a, Message> {
+    use crate::models::PriorityClass;
+
+    let current = current.map(PriorityClass::from_nice);
+    pick_list(&PriorityClass::ALL[..], current, move |class| Message::SetPriorityClass(pid, class))
+        .placeholder("Class")
+        .text_size(14)
+        .padding([4, 8])
+        .width(120.0)
+        .into()
+}
+
+// the same strong warning as a critical kill, realtime can lock up input
+#[cfg(target_family = "windows")]
+pub fn realtime_bar<'a>(pid: i32, name: &str) -> Element<'a, Message> {
+    container(
+        row![
+            column![
+                text(format!("⚠ Set {} (PID {}) to realtime priority?", name, pid))
+                    .size(16)
+                    .shaping(text::Shaping::Advanced),
+                text("A busy realtime process can starve input, drivers and the rest of the system until it hangs.")
+                    .size(14),
+            ]
+            .spacing(2),
+            Space::with_width(Length::Fill),
+            button(text("Set realtime").size(15))
+                .on_press(Message::ConfirmRealtime)
+                .style(iced::theme::Button::Custom(Box::new(KillButton)))
+                .padding([4, 10]),
+            button(text("Cancel").size(15))
+                .on_press(Message::CancelRealtime)
+                .padding([4, 10]),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    )
+    .padding([8, 12])
+    .width(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(StaticBg {
+        bg: Color::from_rgb(0.45, 0.08, 0.08),
+    })))
+    .into()
+}
+
+// menu of common signals, unix only
+#[cfg(target_family = "unix")]
+pub fn signal_picker<'a>(pid: i32) -> Element<'a, Message> {
+    pick_list(&crate::platform::COMMON_SIGNALS[..], None::<crate::models::SignalChoice>, move |sig| {
+        Message::SendSignal(pid, sig.num)
+    })
+    .placeholder("Signal")
+    .text_size(14)
+    .padding([4, 8])
+    .width(100.0)
+    .into()
+}
+
+#[cfg(target_family = "windows")]
+pub fn signal_picker<'a>(_pid: i32) -> Element<'a, Message> {
+    Space::with_width(0).into()
+}
+
+// picks which interface feeds the Net RX/TX graphs
+pub fn network_controls<'a>(rates: &[InterfaceRate], settings: &SettingsModel) -> Element<'a, Message> {
+    let mut options = vec![ALL_INTERFACES.to_string()];
+    options.extend(rates.iter().map(|r| r.name.clone()));
+    let selected = settings
+        .net_interface
+        .clone()
+        .unwrap_or_else(|| ALL_INTERFACES.to_string());
+
+    row![
+        text("Network:").size(14),
+        pick_list(options, Some(selected), Message::NetInterfaceSelected)
+            .text_size(14)
+            .padding([4, 8]),
+        checkbox("Exclude loopback", settings.exclude_loopback)
+            .on_toggle(Message::ExcludeLoopbackChanged),
+        checkbox("Bits/s", settings.net_bits)
+            .on_toggle(Message::NetBitsChanged),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center)
+    .into()
+}
+
+const NOTIFY_COOLDOWNS: [u64; 5] = [60, 300, 600, 1800, 3600];
+const CPU_ALPHAS: [f32; 4] = [0.1, 0.2, 0.3, 0.5];
+// MiB; small boxes want the low end, big servers the high one
+const IDLE_HOG_MIBS: [u64; 7] = [128, 256, 500, 1024, 2048, 4096, 8192];
+const IDLE_HOG_CPUS: [f32; 5] = [0.1, 0.5, 1.0, 2.0, 5.0];
+
 pub fn alert_controls<'a>(settings: &SettingsModel) -> Element<'a, Message> {
     row![
         text("Alerts:").size(14),
@@ -161,52 +1240,137 @@ pub fn alert_controls<'a>(settings: &SettingsModel) -> Element<'a, Message> {
         Space::with_width(10.0),
         checkbox("Memory", settings.alerts_on_mem)
             .on_toggle(Message::MemAlertChanged),
+        Space::with_width(10.0),
+        checkbox("Log to file", settings.alert_log)
+            .on_toggle(Message::AlertLogChanged),
+        Space::with_width(10.0),
+        checkbox("Notify", settings.notify)
+            .on_toggle(Message::NotifyChanged),
+        Space::with_width(6.0),
+        text("every").size(14),
+        Space::with_width(6.0),
+        pick_list(&NOTIFY_COOLDOWNS[..], Some(settings.notify_cooldown_secs), Message::NotifyCooldown)
+            .text_size(14),
+        Space::with_width(6.0),
+        text("s at most").size(14),
+        Space::with_width(20.0),
+        checkbox("Smooth CPU", settings.smooth_cpu)
+            .on_toggle(Message::SmoothCpuChanged),
+        Space::with_width(6.0),
+        // alpha is how much the newest sample counts; lower is smoother
+        text("EMA α (new sample weight)").size(14),
+        Space::with_width(6.0),
+        pick_list(&CPU_ALPHAS[..], Some(settings.cpu_alpha), Message::CpuAlphaChanged)
+            .text_size(14),
+    ]
+    .align_items(Alignment::Center)
+    .into()
+}
+
+// what the idle hog suggestion counts as idle and as big
+pub fn idle_hog_controls<'a>(thresholds: &Thresholds) -> Element<'a, Message> {
+    let mib = thresholds.idle_mem_bytes / (1024 * 1024);
+    row![
+        text("Idle hog: over").size(14),
+        pick_list(&IDLE_HOG_MIBS[..], Some(mib), Message::IdleHogMemChanged).text_size(14),
+        text("MiB under").size(14),
+        pick_list(&IDLE_HOG_CPUS[..], Some(thresholds.idle_cpu_percent), Message::IdleHogCpuChanged).text_size(14),
+        text("% CPU").size(14),
     ]
+    .spacing(6)
     .align_items(Alignment::Center)
     .into()
 }
 
+// watchdog rule: act on processes over a threshold for a while
+pub fn watchdog_controls<'a>(wd: &WatchdogSettings) -> Element<'a, Message> {
+    const METRICS: [WatchMetric; 2] = [WatchMetric::Cpu, WatchMetric::Mem];
+    const THRESHOLDS: [u8; 6] = [50, 60, 70, 80, 90, 95];
+    const DURATIONS: [u64; 6] = [5, 10, 30, 60, 120, 300];
+    const ACTIONS: [WatchAction; 2] = [WatchAction::Suspend, WatchAction::Kill];
+
+    row![
+        checkbox("Watchdog", wd.enabled).on_toggle(Message::WatchdogEnabled),
+        pick_list(&METRICS[..], Some(wd.metric), Message::WatchdogMetric).text_size(14),
+        text("over").size(14),
+        pick_list(&THRESHOLDS[..], Some(wd.threshold), Message::WatchdogThreshold).text_size(14),
+        text("% for").size(14),
+        pick_list(&DURATIONS[..], Some(wd.duration_secs), Message::WatchdogDuration).text_size(14),
+        text("s →").size(14).shaping(text::Shaping::Advanced),
+        pick_list(&ACTIONS[..], Some(wd.action), Message::WatchdogAction).text_size(14),
+    ]
+    .spacing(8)
+    .align_items(Alignment::Center)
+    .into()
+}
+
+// only offered when a tray icon could actually be created
+pub fn tray_controls<'a>(settings: &SettingsModel, tray_available: bool) -> Element<'a, Message> {
+    if tray_available {
+        checkbox("Close to tray", settings.minimize_to_tray)
+            .on_toggle(Message::MinimizeToTrayChanged)
+            .into()
+    } else {
+        Space::with_width(0).into()
+    }
+}
+
+pub fn critical_controls<'a>(settings: &SettingsModel) -> Element<'a, Message> {
+    checkbox("Allow killing critical processes", settings.allow_critical_kill)
+        .on_toggle(Message::AllowCriticalChanged)
+        .into()
+}
+
+fn suggestion_action<'a>(action: Option<SuggestionAction>) -> Element<'a, Message> {
+    match action {
+        Some(SuggestionAction::SignalParent(pid)) => button(text("Signal parent (SIGCHLD)").size(14))
+            .on_press(Message::ReapZombies(pid))
+            .padding([4, 10])
+            .into(),
+        None => Space::with_width(0).into(),
+    }
+}
+
 pub fn suggestions_view<'a>(suggestions: &[Suggestion]) -> Element<'a, Message> {
     if suggestions.is_empty() {
-        container(
-            text("No suggestions. System looks calm.")
-                .size(16)
-                .style(Color::from_rgb(0.4, 0.85, 0.4)),
+        tinted(
+            text("No suggestions. System looks calm.").size(16),
+            Color::from_rgb(0.4, 0.85, 0.4),
         )
         .padding(8)
         .into()
     } else {
         let items = suggestions.iter().map(|s| {
-            let color = if s.title.contains("CPU") {
-                Color::from_rgb(1.0, 0.4, 0.4)
-            } else if s.title.contains("Idle") {
-                Color::from_rgb(0.6, 0.6, 1.0)
-            } else if s.title.contains("Memory") {
-                Color::from_rgb(1.0, 0.8, 0.4)
-            } else {
-                Color::from_rgb(0.9, 0.9, 0.9)
+            let color = match s.category {
+                SuggestionCategory::Cpu => Color::from_rgb(1.0, 0.4, 0.4),
+                SuggestionCategory::Memory => Color::from_rgb(1.0, 0.8, 0.4),
+                SuggestionCategory::Idle => Color::from_rgb(0.6, 0.6, 1.0),
+                SuggestionCategory::Zombie => Color::from_rgb(0.75, 0.75, 0.75),
+                SuggestionCategory::Disk => Color::from_rgb(0.4, 0.8, 1.0),
+                SuggestionCategory::Files | SuggestionCategory::Stopped => Color::from_rgb(0.9, 0.9, 0.9),
             };
 
-            let bg_color = if s.title.contains("CPU") {
-                Color::from_rgb(0.25, 0.1, 0.1)
-            } else if s.title.contains("Idle") {
-                Color::from_rgb(0.15, 0.15, 0.25)
-            } else if s.title.contains("Memory") {
-                Color::from_rgb(0.25, 0.2, 0.1)
-            } else {
-                Color::from_rgb(0.2, 0.2, 0.2)
+            let bg_color = match s.severity {
+                Severity::Critical => Color::from_rgb(0.25, 0.1, 0.1),
+                Severity::Warning => Color::from_rgb(0.25, 0.2, 0.1),
+                Severity::Info => Color::from_rgb(0.2, 0.2, 0.2),
             };
 
             container(
-                column![
-                    text(&s.title)
-                        .size(16)
-                        .style(color),
-                    text(&s.detail)
-                        .size(14)
-                        .style(Color::from_rgb(0.8, 0.8, 0.8)),
+                row![
+                    column![
+                        tinted(text(&s.title).size(16), color),
+                        text(&s.detail).size(14),
+                    ]
+                    .spacing(2),
+                    Space::with_width(Length::Fill),
+                    suggestion_action(s.action),
+                    button(text("×").size(16).shaping(text::Shaping::Advanced))
+                        .on_press(Message::DismissSuggestion(s.key.clone()))
+                        .style(iced::theme::Button::Text)
+                        .padding([0, 8]),
                 ]
-                .spacing(2),
+                .align_items(Alignment::Center),
             )
             .padding([8, 10])
             .style(iced::theme::Container::Custom(Box::new(StaticBg { bg: bg_color })))
@@ -0,0 +1,108 @@
+use process_monitor_and_manager::app::{filter_sort_rows, history_rows, parse_pid_filter};
+use process_monitor_and_manager::models::{ActionRecord, HistoryKey, ProcRow, SettingsModel, SortDir};
+
+fn record(at: u64, pid: i32, name: &str, action: &str, result: Result<(), String>) -> ActionRecord {
+    ActionRecord { at, pid, name: name.to_string(), action: action.to_string(), result }
+}
+
+fn pids(rows: Vec<&ActionRecord>) -> Vec<i32> {
+    rows.into_iter().map(|r| r.pid).collect()
+}
+
+#[test]
+fn history_search_and_sort() {
+    let records = vec![
+        record(100, 30, "firefox", "kill", Ok(())),
+        record(100, 10, "Backup", "suspend", Err("Operation not permitted".into())),
+        record(105, 20, "backupd", "lower", Ok(())),
+    ];
+
+    // newest first by default, same-second records in reverse order taken
+    assert_eq!(pids(history_rows(&records, "", HistoryKey::Time, SortDir::Desc)), vec![20, 10, 30]);
+    assert_eq!(pids(history_rows(&records, "", HistoryKey::Pid, SortDir::Asc)), vec![10, 20, 30]);
+
+    // search is case-insensitive and covers names, actions, pids and errors
+    assert_eq!(pids(history_rows(&records, "BACKUP", HistoryKey::Pid, SortDir::Asc)), vec![10, 20]);
+    assert_eq!(pids(history_rows(&records, "permitted", HistoryKey::Time, SortDir::Desc)), vec![10]);
+    assert_eq!(pids(history_rows(&records, "30", HistoryKey::Time, SortDir::Desc)), vec![30]);
+
+    // failures float to the top
+    assert_eq!(pids(history_rows(&records, "", HistoryKey::Result, SortDir::Desc))[0], 10);
+}
+
+#[test]
+fn pid_list_filter() {
+    let rows: Vec<ProcRow> = [1, 12, 123, 1234]
+        .into_iter()
+        .map(|pid| ProcRow { pid, name: format!("proc{}", pid), ..Default::default() })
+        .collect();
+    let shown = |filter: &str| {
+        let settings = SettingsModel { filter: filter.to_string(), sort_dir: SortDir::Asc, ..Default::default() };
+        filter_sort_rows(&rows, &settings, None).into_iter().map(|p| p.pid).collect::<Vec<_>>()
+    };
+
+    // exact pids, not the substring match that would also catch 123 and 1234
+    assert_eq!(shown("pid:12,1234"), vec![12, 1234]);
+    assert_eq!(shown(" PID: 1 , x, -, 99999 "), vec![1]);
+    assert_eq!(shown("12"), vec![12, 123, 1234]);
+
+    // nothing valid yet leaves the table alone
+    assert_eq!(parse_pid_filter("pid:"), None);
+    assert_eq!(parse_pid_filter("pid:abc"), None);
+    assert_eq!(parse_pid_filter("firefox"), None);
+}
+
+#[test]
+fn ties_keep_pid_order_whatever_the_input_order() {
+    let row = |pid, cpu| ProcRow { pid, cpu, ..Default::default() };
+    let rows = vec![row(40, 0.0), row(7, 5.0), row(12, 0.0), row(3, 0.0), row(9, 5.0)];
+    let mut reversed = rows.clone();
+    reversed.reverse();
+    let settings = SettingsModel::default(); // cpu, descending
+    let order = |rows: &[ProcRow]| filter_sort_rows(rows, &settings, None).into_iter().map(|p| p.pid).collect::<Vec<_>>();
+    assert_eq!(order(&rows), vec![7, 9, 3, 12, 40]);
+    assert_eq!(order(&reversed), order(&rows));
+}
+
+#[test]
+fn only_clearly_late_refreshes_count_as_lagging() {
+    use process_monitor_and_manager::app::sampling_lags;
+
+    assert!(!sampling_lags(0.7));
+    assert!(!sampling_lags(0.9));
+    assert!(sampling_lags(1.5));
+}
+
+#[test]
+fn only_pids_missing_last_refresh_are_new() {
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    use process_monitor_and_manager::app::{new_fade, track_new_pids};
+    use process_monitor_and_manager::models::NEW_PROCESS_HIGHLIGHT;
+
+    let rows = |pids: &[i32]| pids.iter().map(|&pid| ProcRow { pid, ..Default::default() }).collect::<Vec<_>>();
+    let mut new_since = HashMap::new();
+    let t0 = Instant::now();
+
+    // the first refresh has nothing to compare against
+    let seen = track_new_pids(None, &rows(&[1, 2]), &mut new_since, t0);
+    assert!(new_since.is_empty());
+
+    let t1 = t0 + Duration::from_secs(1);
+    let seen = track_new_pids(Some(&seen), &rows(&[1, 2, 3, 4]), &mut new_since, t1);
+    let mut fresh: Vec<i32> = new_since.keys().copied().collect();
+    fresh.sort();
+    assert_eq!(fresh, vec![3, 4]);
+    assert_eq!(new_fade(new_since.get(&3).copied(), t1), 1.0);
+    assert_eq!(new_fade(new_since.get(&1).copied(), t1), 0.0);
+
+    // an exited process drops out, and the rest fade after the highlight
+    let t2 = t1 + Duration::from_secs(1);
+    let seen = track_new_pids(Some(&seen), &rows(&[1, 2, 3]), &mut new_since, t2);
+    assert!(!new_since.contains_key(&4));
+    let fade = new_fade(new_since.get(&3).copied(), t2);
+    assert!(fade > 0.0 && fade < 1.0, "{}", fade);
+    track_new_pids(Some(&seen), &rows(&[1, 2, 3]), &mut new_since, t1 + NEW_PROCESS_HIGHLIGHT);
+    assert!(new_since.is_empty());
+}
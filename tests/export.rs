@@ -0,0 +1,36 @@
+use process_monitor_and_manager::export::{write_json, JsonSnapshot, JSON_SCHEMA_VERSION};
+use process_monitor_and_manager::models::{ProcRow, SystemGraphs, SystemStats};
+
+#[test]
+fn json_snapshot_has_version_and_latest_values() {
+    let stats = SystemStats { cpu_percent: 12.5, ..Default::default() };
+    let mut graphs = SystemGraphs::default();
+    graphs.cpu.push(10.0);
+    graphs.cpu.push(12.5);
+    let rows = [ProcRow { pid: 42, name: "demo".into(), ..Default::default() }];
+
+    let mut buf = Vec::new();
+    write_json(&mut buf, &JsonSnapshot::new(&stats, Some(&graphs), rows.iter().collect(), &[])).unwrap();
+    let doc: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+
+    assert_eq!(doc["schema_version"], JSON_SCHEMA_VERSION);
+    assert_eq!(doc["graphs"]["cpu_percent"], 12.5);
+    // nothing sampled yet reads as null rather than 0
+    assert!(doc["graphs"]["temp_celsius"].is_null());
+    assert_eq!(doc["processes"][0]["pid"], 42);
+    assert_eq!(doc["processes"][0]["name"], "demo");
+}
+
+#[test]
+fn csv_leaves_unknown_io_empty() {
+    use process_monitor_and_manager::export::write_csv;
+
+    let idle = ProcRow { pid: 1, name: "idle".into(), read_bps: Some(0), write_bps: Some(0), ..Default::default() };
+    let hidden = ProcRow { pid: 2, name: "hidden".into(), ..Default::default() };
+    let mut buf = Vec::new();
+    write_csv(&mut buf, &[&idle, &hidden]).unwrap();
+    let text = String::from_utf8(buf).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[1], "1,idle,0.0,0,0,0");
+    assert_eq!(lines[2], "2,hidden,0.0,0,,");
+}
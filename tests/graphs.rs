@@ -0,0 +1,20 @@
+use std::collections::VecDeque;
+
+use process_monitor_and_manager::graphs::{series_stats, trend_arrow};
+
+#[test]
+fn series_stats_cover_the_whole_window() {
+    let points: VecDeque<f32> = [4.0, 1.0, 7.0, 4.0].into_iter().collect();
+    assert_eq!(series_stats(&points), Some((1.0, 4.0, 7.0)));
+    assert_eq!(series_stats(&VecDeque::new()), None);
+}
+
+#[test]
+fn trend_arrow_follows_the_last_step() {
+    let arrow = |v: &[f32]| trend_arrow(&v.iter().copied().collect());
+    assert_eq!(arrow(&[10.0, 20.0]), "↑");
+    assert_eq!(arrow(&[50.0, 20.0, 10.0]), "↓");
+    // jitter within the dead band and a lone point read as flat
+    assert_eq!(arrow(&[100.0, 100.5]), "→");
+    assert_eq!(arrow(&[5.0]), "→");
+}
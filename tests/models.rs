@@ -0,0 +1,56 @@
+use std::time::{Duration, Instant};
+
+use process_monitor_and_manager::models::{parse_env, GraphClock, PriorityClass, SettingsModel, RECENT_FILTERS};
+
+#[test]
+fn env_pairs_parse_and_reject_junk() {
+    assert_eq!(parse_env("").unwrap(), vec![]);
+    assert_eq!(
+        parse_env(" RUST_LOG=debug ; OPTS=--a=1 --b ;").unwrap(),
+        vec![
+            ("RUST_LOG".to_string(), "debug".to_string()),
+            ("OPTS".to_string(), "--a=1 --b".to_string()),
+        ]
+    );
+    assert!(parse_env("JUSTAKEY").is_err());
+    assert!(parse_env("=value").is_err());
+}
+
+#[test]
+fn graph_clock_keeps_one_point_per_interval() {
+    let start = Instant::now();
+    let at = |ms: u64| start + Duration::from_millis(ms);
+    let mut clock = GraphClock::default();
+    // 700ms ticks still come out at one point a second
+    let points: u32 = (0..15).map(|i| clock.due(at(i * 700))).sum();
+    assert_eq!(points, 10);
+    // a late refresh fills the gap, a long pause doesn't
+    assert_eq!(clock.due(at(12_100)), 3);
+    assert_eq!(clock.due(at(60_000)), 1);
+    assert_eq!(clock.due(at(60_500)), 0);
+}
+
+#[test]
+fn priority_classes_round_trip_through_nice() {
+    for class in PriorityClass::ALL {
+        assert_eq!(PriorityClass::from_nice(class.nice()), class);
+    }
+    assert_eq!(PriorityClass::from_nice(5), PriorityClass::BelowNormal);
+    assert_eq!(PriorityClass::from_nice(-15), PriorityClass::High);
+}
+
+#[test]
+fn recent_filters_are_deduped_newest_first_and_capped() {
+    let mut settings = SettingsModel::default();
+    for f in ["chrome", "  ", "pid:1234", " chrome "] {
+        settings.filter = f.to_string();
+        settings.remember_filter();
+    }
+    assert_eq!(settings.recent_filters, vec!["chrome", "pid:1234"]);
+    for i in 0..RECENT_FILTERS + 5 {
+        settings.filter = format!("f{}", i);
+        settings.remember_filter();
+    }
+    assert_eq!(settings.recent_filters.len(), RECENT_FILTERS);
+    assert_eq!(settings.recent_filters[0], format!("f{}", RECENT_FILTERS + 4));
+}
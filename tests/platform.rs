@@ -0,0 +1,195 @@
+#[cfg(target_os = "linux")]
+#[test]
+fn affinity_round_trips_for_own_process() {
+    use process_monitor_and_manager::platform::{get_affinity, set_affinity};
+
+    let pid = std::process::id() as i32;
+    let allowed = get_affinity(pid).unwrap();
+    assert!(!allowed.is_empty());
+    set_affinity(pid, &allowed).unwrap();
+    assert_eq!(get_affinity(pid).unwrap(), allowed);
+    assert!(set_affinity(pid, &[]).is_err());
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn restart_waits_for_exit_and_relaunches() {
+    use process_monitor_and_manager::platform::restart;
+    use sysinfo::{Pid, System};
+
+    let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+    let pid = child.id() as i32;
+    let mut sys = System::new();
+    sys.refresh_process(Pid::from_u32(pid as u32));
+    let start_time = sys.process(Pid::from_u32(pid as u32)).unwrap().start_time();
+
+    // the marker path only reaches the relaunch through the carried environment
+    let marker = std::env::temp_dir().join(format!("procdeck-restart-{}", pid));
+    let argv = ["sh".to_string(), "-c".to_string(), "touch \"$PROCDECK_MARKER\"".to_string()];
+    let env = [("PROCDECK_MARKER".to_string(), marker.display().to_string())];
+    restart(pid, start_time, &argv, None, &env).unwrap();
+    // the old process was asked to stop, not left running
+    let status = child.wait().unwrap();
+    assert_eq!(std::os::unix::process::ExitStatusExt::signal(&status), Some(libc::SIGTERM));
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while !marker.exists() && std::time::Instant::now() < deadline {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    assert!(marker.exists());
+    let _ = std::fs::remove_file(marker);
+
+    assert!(restart(pid, start_time, &[], None, &[]).is_err());
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn restart_of_an_already_exited_process_still_relaunches() {
+    use process_monitor_and_manager::platform::restart;
+
+    // reaped before restart gets to it, so signalling it finds nothing
+    let mut child = std::process::Command::new("true").spawn().unwrap();
+    let pid = child.id() as i32;
+    child.wait().unwrap();
+
+    let marker = std::env::temp_dir().join(format!("procdeck-restart-gone-{}", pid));
+    let argv = ["touch".to_string(), marker.display().to_string()];
+    restart(pid, 0, &argv, None, &[]).unwrap();
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while !marker.exists() && std::time::Instant::now() < deadline {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    assert!(marker.exists());
+    let _ = std::fs::remove_file(marker);
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn shell_join_quotes_only_what_needs_it() {
+    use process_monitor_and_manager::platform::shell_join;
+
+    let argv: Vec<String> = ["echo", "--level=2", "it's here", ""].into_iter().map(String::from).collect();
+    assert_eq!(shell_join(&argv), "echo --level=2 'it'\\''s here' ''");
+    // sh splits it back into the same arguments
+    let script = format!("printf '%s|' {}", shell_join(&argv[1..]));
+    let out = std::process::Command::new("sh").arg("-c").arg(script).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "--level=2|it's here||");
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn only_signals_that_end_a_process_need_the_guard() {
+    use process_monitor_and_manager::platform::{is_terminating_signal, COMMON_SIGNALS};
+
+    let guarded: Vec<&str> = COMMON_SIGNALS.iter().filter(|s| is_terminating_signal(s.num)).map(|s| s.name).collect();
+    assert_eq!(guarded, vec!["SIGHUP", "SIGINT", "SIGQUIT", "SIGTERM", "SIGKILL"]);
+}
+
+#[test]
+fn init_and_listed_names_are_critical() {
+    use process_monitor_and_manager::platform::is_critical;
+
+    if cfg!(target_family = "unix") {
+        assert!(is_critical(1, "anything"));
+    }
+    #[cfg(target_os = "linux")]
+    assert!(is_critical(4321, "Xorg"));
+    #[cfg(target_family = "windows")]
+    assert!(is_critical(4321, "EXPLORER.EXE"));
+    assert!(!is_critical(4321, "firefox"));
+}
+
+#[test]
+fn vm_swap_is_read_from_status() {
+    use process_monitor_and_manager::platform::parse_vm_swap;
+
+    let status = "Name:\tfirefox\nVmRSS:\t  204800 kB\nVmSwap:\t    1536 kB\nThreads:\t42\n";
+    assert_eq!(parse_vm_swap(status), Some(1536 * 1024));
+    // kernel threads have no VmSwap line at all
+    assert_eq!(parse_vm_swap("Name:\tkworker/0:1\nThreads:\t1\n"), None);
+}
+
+#[test]
+fn permission_errors_come_with_a_hint() {
+    use process_monitor_and_manager::platform::{classify_error, explain_error, ErrorClass};
+    use std::io::{Error, ErrorKind};
+
+    let denied = Error::from(ErrorKind::PermissionDenied);
+    assert_eq!(classify_error(&denied), ErrorClass::Permission);
+    assert!(explain_error(&denied).contains(if cfg!(windows) { "administrator" } else { "root" }));
+    assert_eq!(classify_error(&Error::from(ErrorKind::NotFound)), ErrorClass::Gone);
+    assert_eq!(classify_error(&Error::other("boom")), ErrorClass::Other);
+    assert_eq!(explain_error(&Error::other("boom")), "boom");
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn os_errors_from_signals_are_classified() {
+    use process_monitor_and_manager::platform::{classify_error, kill, ErrorClass};
+
+    // a reaped child's pid no longer names anything
+    let mut child = std::process::Command::new("true").spawn().unwrap();
+    let pid = child.id() as i32;
+    child.wait().unwrap();
+    let gone = kill(pid).unwrap_err();
+    assert_eq!(classify_error(&gone), ErrorClass::Gone);
+
+    // init belongs to root; as root the signal would go through, so only
+    // check the refusal for ordinary users
+    if unsafe { libc::geteuid() } != 0 {
+        let denied = kill(1).unwrap_err();
+        assert_eq!(classify_error(&denied), ErrorClass::Permission);
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn kill_tree_leaves_critical_descendants_alone() {
+    use process_monitor_and_manager::platform::kill_tree;
+
+    // a copy of sleep named after a critical process, started under a shell
+    let dir = std::env::temp_dir().join(format!("procdeck-tree-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let fake = dir.join("sshd");
+    std::fs::copy("/bin/sleep", &fake).unwrap();
+    let mut shell = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("\"{}\" 30 & echo $!; wait", fake.display()))
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    std::io::BufRead::read_line(&mut std::io::BufReader::new(shell.stdout.take().unwrap()), &mut line).unwrap();
+    let guarded: i32 = line.trim().parse().unwrap();
+    let comm = format!("/proc/{}/comm", guarded);
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while std::fs::read_to_string(&comm).map_or(true, |c| c.trim() != "sshd") {
+        assert!(std::time::Instant::now() < deadline, "the copy never started");
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+
+    let err = kill_tree(shell.id() as i32).unwrap_err();
+    assert!(err.to_string().contains(&guarded.to_string()), "{}", err);
+    shell.wait().unwrap();
+    // once orphaned it may sit as a zombie if init is slow to reap, so look at its state
+    let alive = std::fs::read_to_string(format!("/proc/{}/stat", guarded))
+        .is_ok_and(|stat| !stat.rsplit(')').next().unwrap_or("").trim_start().starts_with('Z'));
+    nix::sys::signal::kill(nix::unistd::Pid::from_raw(guarded), nix::sys::signal::Signal::SIGKILL).ok();
+    std::fs::remove_dir_all(&dir).ok();
+    assert!(alive);
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn detached_start_gets_its_own_session() {
+    use process_monitor_and_manager::platform::start;
+
+    let mut child = start("sleep 5", None, &[], true).unwrap().unwrap();
+    let pid = nix::unistd::Pid::from_raw(child.id() as i32);
+    let session = nix::unistd::getsid(Some(pid)).unwrap();
+    assert_eq!(session, pid);
+    assert_ne!(session, nix::unistd::getsid(None).unwrap());
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
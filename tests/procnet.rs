@@ -0,0 +1,22 @@
+#![cfg(all(feature = "procnet", target_os = "linux"))]
+
+use process_monitor_and_manager::procnet::{connected_inodes, split_rate};
+
+#[test]
+fn only_connected_sockets_are_counted() {
+    let table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 4242 1 0 100 0 0 10 0
+   1: 0100007F:A1B2 0100007F:1F90 01 00000000:00000000 00:00000000 00000000  1000        0 5151 1 0 20 4 30 10 -1
+   2: 0100007F:A1B3 0100007F:1F90 06 00000000:00000000 03:00000F9E 00000000     0        0 0 3 0";
+    // the listener (0A) and the TIME_WAIT entry (06, inode 0) don't count
+    assert_eq!(connected_inodes(table), vec![5151]);
+}
+
+#[test]
+fn rate_is_split_by_socket_share() {
+    let shares = split_rate(&[Some(3), Some(1), Some(0), None], 4000.0, 400.0);
+    assert_eq!(shares, vec![Some((3000, 300)), Some((1000, 100)), Some((0, 0)), None]);
+
+    // nobody holds a connected socket, so nobody gets traffic
+    assert_eq!(split_rate(&[Some(0)], 4000.0, 400.0), vec![Some((0, 0))]);
+}
@@ -0,0 +1,15 @@
+use process_monitor_and_manager::styles::heat_color;
+
+#[test]
+fn heat_runs_green_to_red() {
+    let cold = heat_color(0.0);
+    let warm = heat_color(0.5);
+    let hot = heat_color(1.0);
+    assert!(cold.g > cold.r);
+    assert!(warm.r > 0.9 && warm.g > 0.8);
+    assert!(hot.r > hot.g);
+    // out of range values stick to the ends
+    assert_eq!(heat_color(3.0), hot);
+    assert_eq!(heat_color(-1.0), cold);
+    assert_eq!(heat_color(f32::NAN), cold);
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use process_monitor_and_manager::models::{DiskUsage, GraphSeries, ProcRow, ProcState, Severity, Thresholds};
+use process_monitor_and_manager::suggestions::{make_suggestions, DISK_FULL_PERCENT, trend_slope, HIGH_OPEN_FILES, LEAK_SAMPLES};
+
+fn keys(rows: &[ProcRow]) -> Vec<String> {
+    make_suggestions(rows, 0.0, 0.0, &Thresholds::default(), &HashMap::new(), 0.0, &[]).into_iter().map(|s| s.key).collect()
+}
+
+#[test]
+fn busy_process_at_raised_priority_is_flagged() {
+    let hot = ProcRow { pid: 10, cpu: 95.0, priority: Some(-5), ..Default::default() };
+    let normal = ProcRow { pid: 11, cpu: 95.0, priority: Some(0), ..Default::default() };
+    let quiet = ProcRow { pid: 12, cpu: 2.0, priority: Some(-10), ..Default::default() };
+    assert_eq!(keys(&[hot, normal, quiet]), vec!["elevated:10"]);
+}
+
+#[test]
+fn only_long_stopped_processes_are_flagged() {
+    let old = ProcRow { pid: 20, status: ProcState::Stopped, stopped_secs: 3600, ..Default::default() };
+    let fresh = ProcRow { pid: 21, status: ProcState::Stopped, stopped_secs: 30, ..Default::default() };
+    assert_eq!(keys(&[old, fresh]), vec!["stopped:20"]);
+}
+
+#[test]
+fn descriptor_heavy_processes_are_flagged() {
+    let leaky = ProcRow { pid: 30, open_files: Some(HIGH_OPEN_FILES + 500), ..Default::default() };
+    let normal = ProcRow { pid: 31, open_files: Some(40), ..Default::default() };
+    let unreadable = ProcRow { pid: 32, open_files: None, ..Default::default() };
+    assert_eq!(keys(&[leaky, normal, unreadable]), vec!["fds:30"]);
+}
+
+#[test]
+fn halted_processes_are_not_cpu_culprits() {
+    // readings from just before the suspend can linger for a tick
+    let stopped = ProcRow { pid: 40, cpu: 99.0, status: ProcState::Stopped, priority: Some(-5), ..Default::default() };
+    let busy = ProcRow { pid: 41, cpu: 60.0, status: ProcState::Running, ..Default::default() };
+    let found = make_suggestions(&[stopped, busy], 95.0, 0.0, &Thresholds::default(), &HashMap::new(), 0.0, &[]);
+    let keys: Vec<&str> = found.iter().map(|s| s.key.as_str()).collect();
+    assert_eq!(keys, vec!["cpu:41"]);
+}
+
+#[test]
+fn suggestions_are_ordered_by_severity() {
+    let idle = ProcRow { pid: 50, mem_bytes: 2 << 30, read_bps: Some(0), write_bps: Some(0), ..Default::default() };
+    let hot = ProcRow { pid: 51, cpu: 95.0, priority: Some(-5), ..Default::default() };
+    let found = make_suggestions(&[idle, hot], 0.0, 0.0, &Thresholds::default(), &HashMap::new(), 0.0, &[]);
+    let order: Vec<(&str, Severity)> = found.iter().map(|s| (s.key.as_str(), s.severity)).collect();
+    assert_eq!(order, vec![("elevated:51", Severity::Warning), ("idle:50", Severity::Info)]);
+}
+
+// memory series stepping from `from` to `to` MiB across the full window,
+// with `dip` MiB taken off one sample halfway
+fn mem_series(from: f32, to: f32, dip: f32) -> GraphSeries {
+    let mut series = GraphSeries::default();
+    for i in 0..LEAK_SAMPLES {
+        let mut mib = from + (to - from) * i as f32 / (LEAK_SAMPLES - 1) as f32;
+        if i == LEAK_SAMPLES / 2 {
+            mib -= dip;
+        }
+        series.push_capped(mib * 1024.0 * 1024.0, LEAK_SAMPLES);
+    }
+    series
+}
+
+#[test]
+fn steadily_growing_large_processes_look_like_leaks() {
+    let rows: Vec<ProcRow> = (60..64)
+        .map(|pid| ProcRow { pid, mem_bytes: 1536 << 20, ..Default::default() })
+        .collect();
+    let history = HashMap::from([
+        (60, mem_series(200.0, 1536.0, 0.0)),
+        (61, mem_series(200.0, 1536.0, 400.0)), // freed some on the way
+        (62, mem_series(10.0, 90.0, 0.0)),      // too small to matter
+        (63, mem_series(1500.0, 1536.0, 0.0)),  // barely moved
+    ]);
+    let found = make_suggestions(&rows, 0.0, 0.0, &Thresholds::default(), &history, 0.7, &[]);
+    let leaks: Vec<&str> = found.iter().map(|s| s.key.as_str()).filter(|k| k.starts_with("leak:")).collect();
+    assert_eq!(leaks, vec!["leak:60"]);
+    assert!(found[0].title.contains("over 1m 23s"), "{}", found[0].title);
+}
+
+#[test]
+fn trend_slope_of_a_line_is_its_step() {
+    let points = (0..10).map(|i| 3.0 * i as f32 + 5.0).collect();
+    assert!((trend_slope(&points) - 3.0).abs() < 1e-4);
+    assert_eq!(trend_slope(&[7.0].into_iter().collect()), 0.0);
+}
+
+#[test]
+fn nearly_full_mounts_are_flagged() {
+    let gib = 1u64 << 30;
+    let disks = [
+        DiskUsage { mount: "/".into(), total: 100 * gib, available: 5 * gib },
+        DiskUsage { mount: "/home".into(), total: 100 * gib, available: 50 * gib },
+        DiskUsage { mount: "/var".into(), total: 100 * gib, available: gib },
+    ];
+    assert!(disks[0].used_percent() >= DISK_FULL_PERCENT);
+    let found = make_suggestions(&[], 0.0, 0.0, &Thresholds::default(), &HashMap::new(), 0.0, &disks);
+    let keys: Vec<&str> = found.iter().map(|s| s.key.as_str()).collect();
+    // the fuller one is critical and sorts first
+    assert_eq!(keys, vec!["disk:/var", "disk:/"]);
+    assert_eq!(found[0].severity, Severity::Critical);
+}
+
+#[test]
+fn idle_hog_cutoffs_come_from_thresholds() {
+    let p = ProcRow { pid: 70, cpu: 0.8, mem_bytes: 300 << 20, read_bps: Some(0), write_bps: Some(0), ..Default::default() };
+    let rows = [p];
+    let idle = |thresholds: &Thresholds| {
+        make_suggestions(&rows, 0.0, 0.0, thresholds, &HashMap::new(), 0.0, &[])
+            .iter()
+            .any(|s| s.key == "idle:70")
+    };
+    // 300 MiB at 0.8% is neither big nor idle enough by default
+    assert!(!idle(&Thresholds::default()));
+    let small_box = Thresholds { idle_mem_bytes: 256 << 20, idle_cpu_percent: 1.0, ..Default::default() };
+    assert!(idle(&small_box));
+    assert!(!idle(&Thresholds { idle_cpu_percent: 0.5, ..small_box }));
+}
+
+#[test]
+fn unknown_io_is_not_taken_for_idle() {
+    let hidden = ProcRow { pid: 80, mem_bytes: 2 << 30, ..Default::default() };
+    assert!(!keys(&[hidden]).iter().any(|k| k == "idle:80"));
+}
@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use process_monitor_and_manager::models::{GraphSeries, IoKey, IoSnapshot, ProcRow, PROC_GRAPH_POINTS};
+use process_monitor_and_manager::system_monitor::{
+    bytes_per_sec, disk_rates, io_rate, ema, prune_dead, prune_row_history, Monitor, MAX_RATE_DT,
+};
+
+fn rows(procs: &[IoKey]) -> Vec<ProcRow> {
+    procs
+        .iter()
+        .map(|&(pid, start_time)| ProcRow { pid, start_time, ..Default::default() })
+        .collect()
+}
+
+// mimics what refresh_now does each tick: record every live process, then prune
+fn tick(last_io: &mut HashMap<IoKey, IoSnapshot>, procs: &[IoKey]) {
+    for &key in procs {
+        last_io.insert(key, IoSnapshot::default());
+    }
+    prune_dead(last_io, &rows(procs));
+}
+
+#[test]
+fn last_io_tracks_live_pids() {
+    let mut last_io = HashMap::new();
+
+    tick(&mut last_io, &[(1, 10), (2, 10), (3, 10)]);
+    assert_eq!(last_io.len(), 3);
+
+    // 2 exits, 4 and 5 start
+    tick(&mut last_io, &[(1, 10), (3, 10), (4, 20), (5, 20)]);
+    assert_eq!(last_io.len(), 4);
+    assert!(!last_io.contains_key(&(2, 10)));
+
+    // a burst of short lived processes comes and goes
+    let burst: Vec<IoKey> = (100..600).map(|pid| (pid, 30)).collect();
+    tick(&mut last_io, &burst);
+    tick(&mut last_io, &[(1, 10)]);
+    assert_eq!(last_io.len(), 1);
+    assert!(last_io.contains_key(&(1, 10)));
+}
+
+#[test]
+fn reused_pid_drops_old_snapshot() {
+    let mut last_io = HashMap::new();
+    last_io.insert((7, 100), IoSnapshot { read: 1 << 40, write: 1 << 40, ..Default::default() });
+
+    // pid 7 now belongs to a process started later
+    tick(&mut last_io, &[(7, 500)]);
+    assert_eq!(last_io.len(), 1);
+    assert_eq!(last_io[&(7, 500)].read, 0);
+}
+
+#[test]
+fn cpu_history_is_short_and_pruned() {
+    let mut history: HashMap<i32, GraphSeries> = HashMap::new();
+    for i in 0..100 {
+        let live = rows(&[(1, 10), (2, 10)]);
+        for p in &live {
+            history.entry(p.pid).or_default().push_capped(i as f32, PROC_GRAPH_POINTS);
+        }
+        prune_row_history(&mut history, &live);
+    }
+    assert_eq!(history[&1].points.len(), PROC_GRAPH_POINTS);
+    assert_eq!(history[&1].points.back(), Some(&99.0));
+
+    prune_row_history(&mut history, &rows(&[(2, 10)]));
+    assert!(!history.contains_key(&1));
+    assert!(history.contains_key(&2));
+}
+
+#[test]
+fn ema_starts_at_first_sample_and_converges() {
+    let mut v = ema(None, 80.0, 0.3);
+    assert_eq!(v, 80.0);
+    // a single spike only moves the average by alpha of the jump
+    v = ema(Some(v), 0.0, 0.3);
+    assert!((v - 56.0).abs() < 1e-4);
+    for _ in 0..50 {
+        v = ema(Some(v), 10.0, 0.3);
+    }
+    assert!((v - 10.0).abs() < 0.01);
+}
+
+#[test]
+fn disk_rate_survives_a_busy_process_exiting() {
+    let busy = ProcRow { pid: 1, read_bps: Some(50_000), write_bps: Some(8_000), ..Default::default() };
+    let quiet = ProcRow { pid: 2, read_bps: Some(300), write_bps: Some(100), ..Default::default() };
+    assert_eq!(disk_rates(&[busy, quiet.clone()]), (50_300.0, 8_100.0));
+
+    // the busy one is gone; what's left keeps reporting its own rate
+    assert_eq!(disk_rates(&[quiet]), (300.0, 100.0));
+}
+
+#[test]
+fn monitor_sample_sees_this_process() {
+    let mut monitor = Monitor::new();
+    let first = monitor.sample();
+    let own = std::process::id() as i32;
+    assert!(first.rows.iter().any(|p| p.pid == own && p.mine));
+    assert_eq!(first.stats.states.total, first.rows.len());
+
+    // a reused buffer comes back refilled rather than appended to
+    let second = monitor.sample_into(first.rows);
+    assert_eq!(second.stats.states.total, second.rows.len());
+    assert!(second.dt > 0.0);
+}
+
+#[test]
+fn bytes_per_sec_handles_tiny_normal_and_huge_gaps() {
+    // 1 KiB over a second
+    assert_eq!(bytes_per_sec(1000, 2024, 1.0), Some(1024.0));
+    // a zero or sub-millisecond gap is floored instead of dividing by ~0
+    for dt in [0.0, 0.000_01] {
+        let rate = bytes_per_sec(0, 1, dt).unwrap();
+        assert!((rate - 1000.0).abs() < 0.1, "{} at dt {}", rate, dt);
+    }
+    // exactly at the limit still counts, past it the sample is skipped
+    assert_eq!(bytes_per_sec(0, 100, MAX_RATE_DT), Some(10.0));
+    assert_eq!(bytes_per_sec(0, 100, MAX_RATE_DT + 0.5), None);
+    assert_eq!(bytes_per_sec(0, 100, f32::NAN), None);
+}
+
+#[test]
+fn bytes_per_sec_skips_counter_resets() {
+    assert_eq!(bytes_per_sec(5000, 100, 1.0), None);
+    assert_eq!(bytes_per_sec(5000, 5000, 1.0), Some(0.0));
+}
+
+#[test]
+fn resets_carry_the_last_rate_and_glitches_read_zero() {
+    // the counter restarted from near zero: keep showing the previous rate
+    assert_eq!(io_rate(1 << 30, 4096, 1.0, 2048), 2048);
+    // the next sample measures from the new base
+    assert_eq!(io_rate(4096, 8192, 1.0, 2048), 4096);
+    // a slight dip is jitter, not a reset, and nothing moved
+    assert_eq!(bytes_per_sec(5000, 4990, 1.0), Some(0.0));
+    assert_eq!(io_rate(5000, 4990, 1.0, 2048), 0);
+    // a stale gap isn't a reset, so nothing is carried
+    assert_eq!(io_rate(0, 100, MAX_RATE_DT + 1.0, 2048), 0);
+}
+
+#[test]
+fn load_average_only_on_unix() {
+    let stats = Monitor::new().sample().stats;
+    assert_eq!(stats.load_avg.is_some(), cfg!(unix));
+}
+
+#[test]
+fn uptime_matches_boot_time() {
+    let stats = Monitor::new().sample().stats;
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+    assert!(stats.uptime_secs > 0);
+    // both come from the same clock, allow for a little drift
+    assert!((stats.boot_time + stats.uptime_secs).abs_diff(now) < 60);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn own_io_is_visible() {
+    let own = std::process::id() as i32;
+    let snap = Monitor::new().sample();
+    let row = snap.rows.iter().find(|p| p.pid == own).unwrap();
+    assert!(row.read_bps.is_some() && row.write_bps.is_some());
+}
+
+#[test]
+fn swap_is_only_read_while_something_shows_it() {
+    use process_monitor_and_manager::models::{ColumnId, SettingsModel, SortKey};
+    use process_monitor_and_manager::system_monitor::ExtraReads;
+
+    let settings = SettingsModel::default();
+    assert!(!settings.columns.contains(&ColumnId::Swap));
+    assert!(!ExtraReads::for_settings(&settings, Some(7)).swap);
+
+    let mut shown = settings.clone();
+    shown.columns.push(ColumnId::Swap);
+    assert!(ExtraReads::for_settings(&shown, None).swap);
+    let sorted = SettingsModel { sort_key: SortKey::Swap, ..settings };
+    assert!(ExtraReads::for_settings(&sorted, None).swap);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn fd_counts_carry_over_between_full_scans() {
+    let me = std::process::id() as i32;
+    let mut monitor = Monitor::new();
+    // nothing on screen wants fds, so only the periodic scan counts them
+    let first = monitor.sample();
+    let counted = first.rows.iter().find(|p| p.pid == me).and_then(|p| p.open_files);
+    assert!(counted.is_some_and(|n| n > 0));
+    let second = monitor.sample();
+    assert!(second.rows.iter().find(|p| p.pid == me).and_then(|p| p.open_files).is_some());
+}
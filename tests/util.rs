@@ -0,0 +1,46 @@
+use process_monitor_and_manager::util::{fmt_bits_per_sec, fmt_bytes, fmt_parent, fmt_utc};
+
+#[test]
+fn bits_per_sec_uses_decimal_prefixes() {
+    // 125 bytes/s is exactly 1000 bits/s
+    assert_eq!(fmt_bits_per_sec(124.875), "999.0 bps");
+    assert_eq!(fmt_bits_per_sec(125.0), "1.0 Kbps");
+    // 1024 bits/s is still just over 1 Kbps, not a binary boundary
+    assert_eq!(fmt_bits_per_sec(128.0), "1.0 Kbps");
+}
+
+#[test]
+fn bits_per_sec_scales_through_units() {
+    assert_eq!(fmt_bits_per_sec(0.0), "0.0 bps");
+    assert_eq!(fmt_bits_per_sec(125_000.0), "1.0 Mbps");
+    assert_eq!(fmt_bits_per_sec(12_500_000.0), "100.0 Mbps");
+    assert_eq!(fmt_bits_per_sec(125_000_000.0), "1.0 Gbps");
+}
+
+#[test]
+fn fmt_bytes_switches_unit_at_1024() {
+    assert_eq!(fmt_bytes(1023), "1023.0 B");
+    assert_eq!(fmt_bytes(1024), "1.0 KB");
+    assert_eq!(fmt_bytes(1025), "1.0 KB");
+    assert_eq!(fmt_bytes(1_048_576), "1.0 MB");
+}
+
+#[test]
+fn fmt_bytes_caps_at_terabytes() {
+    assert_eq!(fmt_bytes(5 * 1024u64.pow(4)), "5.0 TB");
+    assert_eq!(fmt_bytes(2048 * 1024u64.pow(4)), "2048.0 TB");
+}
+
+#[test]
+fn utc_timestamps() {
+    assert_eq!(fmt_utc(0), "1970-01-01 00:00:00Z");
+    assert_eq!(fmt_utc(951_782_400), "2000-02-29 00:00:00Z");
+    assert_eq!(fmt_utc(1_700_000_000), "2023-11-14 22:13:20Z");
+}
+
+#[test]
+fn parent_shows_name_or_gone() {
+    assert_eq!(fmt_parent(Some(1), Some("systemd")), "systemd (1)");
+    assert_eq!(fmt_parent(Some(4242), None), "4242 (gone)");
+    assert_eq!(fmt_parent(None, None), "-");
+}
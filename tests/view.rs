@@ -0,0 +1,68 @@
+use process_monitor_and_manager::models::{ColumnId, ProcRow};
+use process_monitor_and_manager::view::{env_entries, row_text};
+
+#[test]
+fn copied_row_leads_with_the_basics() {
+    let p = ProcRow {
+        pid: 42,
+        name: "demo".into(),
+        cpu: 12.5,
+        mem_bytes: 2048,
+        read_bps: Some(0),
+        threads: 3,
+        ..Default::default()
+    };
+    let text = row_text(&p, &[ColumnId::Name, ColumnId::Threads, ColumnId::Pid]);
+    let fields: Vec<&str> = text.split('\t').collect();
+    assert_eq!(fields[0], "PID: 42");
+    assert_eq!(fields[1], "Name: demo");
+    assert_eq!(fields[2], "CPU %: 12.5");
+    // unknown I/O reads as unknown, and extra columns follow once
+    assert_eq!(fields[5], "Write/s: —");
+    assert_eq!(fields.len(), 7);
+    assert_eq!(fields[6], "Threads: 3");
+}
+
+#[test]
+fn every_unreadable_column_reads_the_same() {
+    use process_monitor_and_manager::view::{cell_text, UNKNOWN};
+
+    let p = ProcRow { pid: 1, ..Default::default() };
+    for id in [
+        ColumnId::Priority,
+        ColumnId::Swap,
+        ColumnId::Read,
+        ColumnId::Write,
+        ColumnId::ReadTotal,
+        ColumnId::WriteTotal,
+        ColumnId::Threads,
+        ColumnId::OpenFiles,
+        ColumnId::NetRx,
+        ColumnId::NetTx,
+    ] {
+        assert_eq!(cell_text(id, &p), UNKNOWN, "{:?}", id);
+    }
+    let known = ProcRow { read_total: Some(0), ..p };
+    assert_eq!(cell_text(ColumnId::ReadTotal, &known), "0.0 B");
+}
+
+#[test]
+fn environment_masks_secrets_until_revealed() {
+    let environ: Vec<(String, String)> = [("HOME", "/home/me"), ("GITHUB_TOKEN", "ghp_x"), ("api_key", "k"), ("LANG", "C")]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    let masked = env_entries(&environ, "", false);
+    assert_eq!(masked[0], ("HOME", "/home/me".to_string()));
+    assert_ne!(masked[1].1, "ghp_x");
+    assert_ne!(masked[2].1, "k");
+    assert_eq!(env_entries(&environ, "", true)[1].1, "ghp_x");
+
+    // the filter matches names and values, ignoring case
+    let keys = |filter| env_entries(&environ, filter, false).into_iter().map(|(k, _)| k).collect::<Vec<_>>();
+    assert_eq!(keys("home"), vec!["HOME"]);
+    assert_eq!(keys(" lang "), vec!["LANG"]);
+    // a masked value can't be probed through the filter
+    assert!(keys("ghp").is_empty());
+    assert_eq!(env_entries(&environ, "ghp", true)[0].0, "GITHUB_TOKEN");
+}